@@ -32,10 +32,52 @@
 //!   APIs for dataflow recording and shadow call-stack frame management for
 //!   use in the code generated by the [wye] and [wyre] macros.
 //! 
-//! * [WyeArgs] and [WyeArgMap] are used by [wye] to support custom formatting.
+//! * [WyeArgs] and [WyeArgMap] are used by [wye] and [wyre] to support
+//!   custom formatting; see [Parts::format]. [Filters] select the
+//!   functions that [wye] instruments in modules, impl blocks, and traits.
 //! 
 //! * [process_stmts] is the root of the common logic shared by [wye] and [wyre].
 //! 
+//! ## Site IDs
+//! 
+//! Each instrumented site is identified at run-time by a `u64` slot, its
+//! site ID, which is the 64-bit FNV-1a hash of the bytes of:
+//! 
+//! 1. the crate name (`CARGO_CRATE_NAME`), then a `0` byte,
+//! 2. the source file path, relative to `CARGO_MANIFEST_DIR` when it lies
+//!    inside it, with `/` separators, then a `0` byte,
+//! 3. the site's start line, start column, end line, and end column, each
+//!    as a little-endian `u32`, with 1-based lines and columns.
+//! 
+//! Site IDs therefore do not change across builds, machines, or compiler
+//! versions unless the instrumented code moves, and identical snippets in
+//! different files or crates get different IDs. [wye::trace::site_id]
+//! recomputes them at run-time.
+//! 
+//! ## Site Manifest
+//! 
+//! Besides instrumenting their input, [wye] and [wyre] describe each site
+//! they instrument -- its site ID, enclosing function, file, span, source
+//! text, and syntactic kind -- in a site manifest, so that offline tools
+//! can report on sites that never executed. See `wye::manifest`.
+//! 
+//! Each macro invocation writes one file of JSON lines, named after the
+//! crate and the invocation's own site ID, to the directory named by the
+//! `WYE_MANIFEST_DIR` environment variable or, failing that, to the
+//! `wye-sites` directory under `OUT_DIR`, if the crate has a build script.
+//! 
+//! ## Errors
+//! 
+//! Code that [wye] and [wyre] cannot instrument is reported as a compile
+//! error at its span, naming the construct, rather than left to panic or
+//! to fail in the generated code: `const fn`s, whose bodies run at compile
+//! time; `async fn`s, blocks, and closures, whose frames would interleave
+//! with those of other tasks; and items other than fns, impls, traits, and
+//! mods. Custom formatters for names that are never recorded are reported
+//! the same way. Functions reached through an annotated module, impl, or
+//! trait, as in the build-script mode, are not annotated themselves, so
+//! the code in them that cannot be instrumented is left as it is instead.
+//! 
 //! ## Method
 //! 
 //! I need a way to take an expression, 
@@ -50,12 +92,12 @@
 //! 
 //! * [wye](https://github.com/mstone/wye)
 #![feature(proc_macro_span)]
-use std::{collections::{HashMap, HashSet, hash_map::DefaultHasher}, fmt::Display, hash::{Hash, Hasher}, ops::Range};
+use std::{collections::{HashMap, HashSet}, fmt::{Display, Write}, ops::Range, path::PathBuf};
 
 use proc_macro2::{TokenStream, Span};
-use quote::{ToTokens, TokenStreamExt, format_ident};
+use quote::{ToTokens, TokenStreamExt, format_ident, quote};
 use rangemap::RangeMap;
-use syn::{parse_macro_input, Item, Expr, punctuated::Punctuated, token::{Comma}, Block, Stmt, Ident, parenthesized, visit::Visit, visit_mut::VisitMut, spanned::Spanned, PatIdent, ItemFn, parse_quote, BinOp, ExprCall, Signature, ExprMacro, parse2, ExprLet, Local,};
+use syn::{parse_macro_input, Item, Expr, punctuated::Punctuated, token::{Comma}, Block, Stmt, Ident, parenthesized, visit::Visit, visit_mut::VisitMut, spanned::Spanned, PatIdent, ItemFn, parse_quote, parse_quote_spanned, BinOp, ExprAssign, ExprAssignOp, ExprBinary, ExprCall, ExprMethodCall, ExprReference, ImplItemMethod, ItemImpl, ItemTrait, TraitItemMethod, Receiver, ExprUnary, UnOp, Signature, ExprMacro, ExprLet, Local, Attribute, DeriveInput, Data, Fields, ItemMod, ExprIf, ExprMatch, Arm, ExprBlock, ExprForLoop, ExprWhile, ExprLoop, ExprClosure, ExprReturn, ExprTry, ExprBreak, ExprAsync, ExprParen, ExprCast, ExprField, ExprIndex, ExprTuple, ExprArray, ExprStruct,};
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 struct LineColumn {
@@ -63,6 +105,15 @@ struct LineColumn {
     column: usize,
 }
 
+impl LineColumn {
+    /// Returns the 1-based `(line, column)` of this position, as recorded in
+    /// site IDs, [wye::trace::Location]s and the site manifest;
+    /// `proc_macro` already counts both from 1.
+    fn position(&self) -> (u32, u32) {
+        (self.line as u32, self.column as u32)
+    }
+}
+
 impl From<proc_macro::LineColumn> for LineColumn {
     fn from(lc: proc_macro::LineColumn) -> Self {
         Self {
@@ -96,13 +147,17 @@ impl Bytespan {
             end: span.end().into(),
         }
     }
-}
-
-struct Place {}
 
-impl Display for Place {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Place")
+    /// Returns the stable site ID of this span; see "Site IDs" above.
+    fn site_id(&self) -> u64 {
+        let (start_line, start_column) = self.start.position();
+        let (end_line, end_column) = self.end.position();
+        let Fnv(site_id) = Fnv(self.source_hash)
+            .write(&start_line.to_le_bytes())
+            .write(&start_column.to_le_bytes())
+            .write(&end_line.to_le_bytes())
+            .write(&end_column.to_le_bytes());
+        site_id
     }
 }
 
@@ -121,8 +176,8 @@ impl Display for Source {
 impl Source {
     fn new_from_ident(source_hash: u64, ident: &Ident) -> Self {
         Self {
-            ident: ident.span().unwrap().source_text().unwrap(),
-            bytespan: Bytespan::new(source_hash, ident.span().into()),
+            ident: ident.span().unwrap().source_text().unwrap_or_else(|| ident.to_string()),
+            bytespan: Bytespan::new(source_hash, ident.span()),
         }
     }
 }
@@ -166,15 +221,69 @@ impl Uses {
     }
 }
 
+impl Uses {
+    /// Records the identifiers in `tokens`, those of a macro invocation
+    /// that is not parsed, as uses.
+    fn visit_tokens(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                proc_macro2::TokenTree::Ident(ident) => self.visit_ident(&ident),
+                proc_macro2::TokenTree::Group(group) => self.visit_tokens(group.stream()),
+                _ => {},
+            }
+        }
+    }
+
+    /// Records the variables that `format`, a format string, captures
+    /// inline, like `x` in `"{x:?}"`, as uses, except for those that are
+    /// `named` arguments, like `a` in `format!("{a}", a = x)`.
+    fn visit_format_string(&mut self, format: &syn::LitStr, named: &[String]) {
+        let chars = format.token().to_string().chars().collect::<Vec<_>>();
+        // the position of each character of the literal, which may span
+        // lines:
+        let mut positions = Vec::with_capacity(chars.len() + 1);
+        let mut position = format.span().unwrap().start();
+        for c in &chars {
+            positions.push(position);
+            position = match c {
+                '\n' => proc_macro::LineColumn{line: position.line + 1, column: 1},
+                _ => proc_macro::LineColumn{column: position.column + 1, ..position},
+            };
+        }
+        positions.push(position);
+        let mut index = 0;
+        while index < chars.len() {
+            // `{{` is an escaped brace:
+            if chars[index] != '{' || chars.get(index + 1) == Some(&'{') {
+                index += if chars[index] == '{' { 2 } else { 1 };
+                continue;
+            }
+            let start = index + 1;
+            let end = start + chars[start..].iter().take_while(|c| c.is_alphanumeric() || **c == '_').count();
+            let name = chars[start..end].iter().collect::<String>();
+            let is_ident = name.starts_with(|c: char| c.is_alphabetic() || c == '_') && name != "_";
+            if is_ident && matches!(chars.get(end), Some('}' | ':')) && !named.contains(&name) {
+                self.uses.insert(positions[start]..positions[end], Use{ident: name});
+            }
+            index = end;
+        }
+    }
+}
+
 impl<'ast> Visit<'ast> for Uses {
     fn visit_expr_macro(&mut self, node: &ExprMacro) {
-        let syn::ExprMacro{mac, ..} = node;
-        let syn::Macro{path, tokens, ..} = mac;
-        if path.get_ident().is_some() {
-            let format_args: FormatArgs = parse2(tokens.clone()).unwrap();
-            for arg in format_args.0.iter().skip(1) {
-                self.visit_expr(arg);
-            }
+        // the variables in other macros' tokens are all inputs of their
+        // opaque nodes:
+        match MacroArgs::parse(&node.mac) {
+            Some(mut args) => {
+                if let Some((format, named)) = args.format_string() {
+                    self.visit_format_string(&format, &named);
+                }
+                for arg in args.exprs_mut() {
+                    self.visit_expr(arg);
+                }
+            },
+            None => self.visit_tokens(node.mac.tokens.clone()),
         }
     }
 
@@ -191,7 +300,9 @@ struct Scopes {
     #[allow(dead_code)]
     source_hash: u64,
     scopestack: Vec<(ScopeKind, proc_macro::LineColumn, proc_macro::LineColumn, Sources)>,
-    scopes: RangeMap<proc_macro::LineColumn, (ScopeKind, Sources)>,
+    /// Scopes nest, so, unlike [Uses], they are kept in a list rather than
+    /// a [RangeMap], in which outer scopes would overwrite inner ones.
+    scopes: Vec<(Range<proc_macro::LineColumn>, ScopeKind, Sources)>,
 }
 
 
@@ -207,7 +318,7 @@ impl Scopes {
         Self{
             source_hash,
             scopestack: vec![],
-            scopes: RangeMap::new(),
+            scopes: vec![],
         }
     }
 
@@ -217,7 +328,7 @@ impl Scopes {
 
     fn pop_scope(&mut self, _end: proc_macro::LineColumn) {
         if let Some((kind, start, end, scope)) = self.scopestack.pop() {
-            self.scopes.insert(start..end, (kind, scope));
+            self.scopes.push((start..end, kind, scope));
         }
     }
 }
@@ -230,6 +341,27 @@ impl<'ast> Visit<'ast> for Scopes {
         self.pop_scope(span.end());
     }
 
+    fn visit_impl_item_method(&mut self, method: &'ast ImplItemMethod) {
+        let span = method.span().unwrap();
+        self.push_scope(ScopeKind::Fn, span.start(), span.end());
+        syn::visit::visit_impl_item_method(self, method);
+        self.pop_scope(span.end());
+    }
+
+    fn visit_trait_item_method(&mut self, method: &'ast TraitItemMethod) {
+        let span = method.span().unwrap();
+        self.push_scope(ScopeKind::Fn, span.start(), span.end());
+        syn::visit::visit_trait_item_method(self, method);
+        self.pop_scope(span.end());
+    }
+
+    fn visit_receiver(&mut self, receiver: &'ast Receiver) {
+        let ident = Ident::new("self", receiver.self_token.span);
+        if let Some(scope) = self.scopestack.last_mut() {
+            scope.3.insert(Source::new_from_ident(self.source_hash, &ident));
+        }
+    }
+
     fn visit_block(&mut self, block: &'ast Block) {
         let span = block.span().unwrap();
         self.push_scope(ScopeKind::Block, span.start(), span.end());
@@ -238,34 +370,138 @@ impl<'ast> Visit<'ast> for Scopes {
     }
 
     fn visit_local(&mut self, node: &'ast Local) {
+        // the variables a let-binding binds are in scope after it, and not
+        // in its own initializer:
         let span = node.span().unwrap();
-        let prev_end = self.scopestack.last().unwrap().2;
-        self.push_scope(ScopeKind::Local, span.start(), prev_end);
+        let prev_end = self.scopestack.last().map_or(span.end(), |scope| scope.2);
+        self.push_scope(ScopeKind::Local, span.end(), prev_end);
         syn::visit::visit_local(self, node);
         self.pop_scope(prev_end);
     }
 
     fn visit_expr_let(&mut self, node: &'ast ExprLet) {
         let span = node.span().unwrap();
-        let prev_end = self.scopestack.last().unwrap().2;
+        let prev_end = self.scopestack.last().map_or(span.end(), |scope| scope.2);
         self.push_scope(ScopeKind::Local, span.start(), prev_end);
         syn::visit::visit_expr_let(self, node);
         self.pop_scope(prev_end);
     }
 
+    fn visit_expr_if(&mut self, node: &'ast ExprIf) {
+        // the variables an if-let binds are in scope in its then-branch, and
+        // not in its scrutinee or its else-branch:
+        let Expr::Let(expr_let) = node.cond.as_ref() else {
+            return syn::visit::visit_expr_if(self, node);
+        };
+        self.visit_expr(&expr_let.expr);
+        let span = node.then_branch.span().unwrap();
+        self.push_scope(ScopeKind::Local, span.start(), span.end());
+        self.visit_pat(&expr_let.pat);
+        self.visit_block(&node.then_branch);
+        self.pop_scope(span.end());
+        if let Some((_, else_branch)) = &node.else_branch {
+            self.visit_expr(else_branch);
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast ExprForLoop) {
+        // the variables a for loop's pattern binds are in scope in its body,
+        // and not in its iterable:
+        self.visit_expr(&node.expr);
+        let span = node.body.span().unwrap();
+        self.push_scope(ScopeKind::Local, span.start(), span.end());
+        self.visit_pat(&node.pat);
+        self.visit_block(&node.body);
+        self.pop_scope(span.end());
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast ExprWhile) {
+        // as with if-let, the variables a while-let binds are in scope in
+        // its body:
+        let Expr::Let(expr_let) = node.cond.as_ref() else {
+            return syn::visit::visit_expr_while(self, node);
+        };
+        self.visit_expr(&expr_let.expr);
+        let span = node.body.span().unwrap();
+        self.push_scope(ScopeKind::Local, span.start(), span.end());
+        self.visit_pat(&expr_let.pat);
+        self.visit_block(&node.body);
+        self.pop_scope(span.end());
+    }
+
+    fn visit_expr_closure(&mut self, node: &'ast ExprClosure) {
+        // a closure's parameters are in scope in its body:
+        let span = node.body.span().unwrap();
+        self.push_scope(ScopeKind::Local, span.start(), span.end());
+        for input in &node.inputs {
+            self.visit_pat(input);
+        }
+        self.visit_expr(&node.body);
+        self.pop_scope(span.end());
+    }
+
+    fn visit_arm(&mut self, arm: &'ast Arm) {
+        // the variables a match arm binds are in scope in its guard and body:
+        let start = match &arm.guard {
+            Some((if_token, _)) => if_token.span.unwrap().start(),
+            None => arm.body.span().unwrap().start(),
+        };
+        let end = arm.body.span().unwrap().end();
+        self.push_scope(ScopeKind::Local, start, end);
+        syn::visit::visit_arm(self, arm);
+        self.pop_scope(end);
+    }
+
     fn visit_pat_ident(&mut self, ident: &'ast PatIdent) {
-        self.scopestack
-            .last_mut()
-            .map(|ref mut scope| {
-                scope.3.insert(Source::new_from_ident(self.source_hash, &ident.ident))
-            });
+        if !is_binder(ident) {
+            return;
+        }
+        // the alternatives of an or-pattern, like `A(x) | B(x)`, bind the
+        // same variables, which are recorded at their first binders:
+        let source = Source::new_from_ident(self.source_hash, &ident.ident);
+        if let Some(scope) = self.scopestack.last_mut() {
+            if !scope.3.0.iter().any(|source| ident.ident == source.ident) {
+                scope.3.insert(source);
+            }
+        }
     }
 }
 
 struct Parts<'ast> {
     source_hash: u64,
+    file: String,
     scopes: &'ast Scopes,
     uses: &'ast Uses,
+    function: Option<String>,
+    /// The type or trait whose methods are being instrumented, if any.
+    owner: Option<String>,
+    /// The modules, within the annotated item, being instrumented.
+    modules: Vec<String>,
+    sites: Vec<Site>,
+    formats: WyeArgMap,
+    formatted: HashSet<Ident>,
+    filters: Filters,
+    /// The type ascribed to the let-binding being compiled, like `u64` in
+    /// `let sum: u64 = xs.iter().sum();`, if any.
+    ascription: Option<Box<syn::Type>>,
+    /// The binder slots of the `&mut` parameters and receiver of the
+    /// function being instrumented.
+    mut_params: Vec<u64>,
+    /// The slot of the call whose arguments are being instrumented, if any.
+    call: Option<u64>,
+    /// The source ranges of the operands of the expression being compiled
+    /// that got nodes of their own; see [Parts::visit_operand_mut].
+    operands: Vec<Range<proc_macro::LineColumn>>,
+    /// The labels, if any, of the loops around the expression being
+    /// instrumented, within its function or closure, innermost last.
+    loop_labels: Vec<Option<String>>,
+    /// Code wye cannot instrument, reported once the item is visited.
+    errors: Vec<syn::Error>,
+    /// Whether the function being instrumented was reached through an
+    /// annotated module, impl, or trait rather than annotated itself, in
+    /// which case code that wye cannot instrument is left as it is rather
+    /// than reported.
+    implicit: bool,
 }
 
 type UseRange = Range<proc_macro::LineColumn>;
@@ -273,49 +509,917 @@ type SourceRange = Range<proc_macro::LineColumn>;
 type Binding = (UseRange, Use, SourceRange, ScopeKind, Source);
 
 impl<'ast> Parts<'ast> {
-    fn new(source_hash: u64, scopes: &'ast Scopes, uses: &'ast Uses) -> Self {
+    fn new(source_hash: u64, file: String, scopes: &'ast Scopes, uses: &'ast Uses, (formats, filters): (WyeArgMap, Filters)) -> Self {
         Self{
             source_hash,
+            file,
             scopes,
             uses,
+            function: None,
+            owner: None,
+            modules: vec![],
+            sites: vec![],
+            formats,
+            formatted: HashSet::new(),
+            filters,
+            ascription: None,
+            mut_params: vec![],
+            call: None,
+            operands: vec![],
+            loop_labels: vec![],
+            errors: vec![],
+            implicit: false,
+        }
+    }
+
+    /// Generates an expression that formats `value`, a reference to the
+    /// value bound to `ident`, with the custom formatter given for `ident`,
+    /// if any, or else with [capture].
+    ///
+    /// Custom formatters see `ident` bound to `value`, so that both
+    /// `#[wye(a: format!("{a}"))]` and `#[wye(a: a.len().to_string())]`
+    /// work whether `a` is a parameter, a `let`-binding, or a variable that
+    /// is free in a [wyre] invocation.
+    fn format(&mut self, ident: &Ident, value: Expr) -> Expr {
+        match self.formats.get_key_value(ident) {
+            Some((ident, format)) => {
+                self.formatted.insert(ident.clone());
+                parse_quote!({
+                    #[allow(unused_variables)]
+                    let #ident = #value;
+                    #format
+                })
+            },
+            None => capture(value),
+        }
+    }
+
+    /// Records that `construct`, at `span`, cannot be instrumented, unless
+    /// it was reached implicitly.
+    fn unsupported(&mut self, span: Span, construct: &str) {
+        if !self.implicit {
+            self.errors.push(syn::Error::new(span, format!("wye cannot instrument {construct}")));
+        }
+    }
+
+    /// Reports the code that could not be instrumented, and custom
+    /// formatters given for names that were never recorded.
+    fn check(&self) -> syn::Result<()> {
+        let mut unknown = self.formats.keys()
+            .filter(|ident| !self.formatted.contains(*ident))
+            .collect::<Vec<_>>();
+        unknown.sort_by_key(|ident| ident.to_string());
+        let mut errors = self.errors.iter().cloned().chain(unknown.into_iter().map(|ident| {
+            syn::Error::new(ident.span(), format!("no parameter or binding named `{ident}` to format"))
+        }));
+        match errors.next() {
+            None => Ok(()),
+            Some(mut error) => {
+                error.extend(errors);
+                Err(error)
+            },
         }
     }
 
+    /// Records `slot` in the site manifest.
+    fn site(&mut self, slot: u64, span: Span, text: &str, kind: String) {
+        self.sites.push(Site{
+            id: slot,
+            function: self.function.clone(),
+            bytespan: Bytespan::new(self.source_hash, span),
+            text: text.to_string(),
+            kind,
+        });
+    }
+
+    /// Returns the variables that `expr` uses, with the scopes and binders
+    /// they resolve to, except for those bound inside `expr` itself, like
+    /// those of a match's arms, which are not inputs to it.
     fn bindings(&self, expr: &Expr) -> Vec<Binding> {
         let expr_start = expr.span().unwrap().start();
         let expr_end = expr.span().unwrap().end();
         let expr_range = expr_start..expr_end;
-        let scopes = self.scopes.scopes.overlapping(&expr_range);
-        let scopes = scopes.collect::<Vec<_>>();
+        let Bytespan{start, end, ..} = Bytespan::new(self.source_hash, expr.span());
         let uses = self.uses.uses.overlapping(&expr_range);
         let uses = uses.collect::<Vec<_>>();
         let mut bindings: Vec<(_, Use, _, ScopeKind, Source)> = vec![];
 
         for (var_range, var) in uses.iter().cloned() {
-            for (scope_range, (scope_kind, sources)) in scopes.iter().cloned().rev() {
-                if scope_range.contains(&var_range.start) && scope_range.contains(&var_range.end) {
-                    sources.0.iter().find(|source| source.ident == var.ident).map(|source| {
-                        bindings.push((var_range.clone(), var.clone(), scope_range.clone(), *scope_kind, source.clone()));
-                    });
-                    break;
+            // the innermost scope binding var is the one that starts last,
+            // or, of those, ends first; a use may end where its scope does,
+            // like `h` in the match arm `Rect{w, h} => w * h`:
+            let scope = self.scopes.scopes.iter()
+                .filter(|(scope_range, ..)| scope_range.start <= var_range.start && var_range.end <= scope_range.end)
+                .filter_map(|(scope_range, scope_kind, sources)| {
+                    let source = sources.0.iter().find(|source| source.ident == var.ident)?;
+                    Some((scope_range, scope_kind, source))
+                })
+                .max_by_key(|(scope_range, ..)| (scope_range.start, std::cmp::Reverse(scope_range.end)));
+            if let Some((scope_range, scope_kind, source)) = scope {
+                if source.bytespan.start >= start && source.bytespan.start < end {
+                    continue;
                 }
+                bindings.push((var_range.clone(), var.clone(), scope_range.clone(), *scope_kind, source.clone()));
             }
         }
 
         bindings
     }
 
+    /// Generates a call recording the source location of the node at
+    /// `frame`, `slot`.
+    fn locate(&self, frame: &str, slot: u64, span: Span, text: &str) -> Stmt {
+        let frame = format_ident!("{}", frame);
+        let file = &self.file;
+        let Bytespan{start, end, ..} = Bytespan::new(self.source_hash, span);
+        let (start_line, start_column) = start.position();
+        let (end_line, end_column) = end.position();
+        parse_quote!(
+            __wye.locate(#frame, #slot, #file, (#start_line, #start_column), (#end_line, #end_column), #text);
+        )
+    }
+
+    /// Generates edges to the node at `frame`, `place` from the parameters
+    /// and let-bindings in `bindings`, once for each, however many times
+    /// they are used.
+    fn edges(bindings: &[Binding], frame: &Ident, place: u64) -> Vec<Stmt> {
+        let mut seen = HashSet::new();
+        bindings.iter().filter_map(|(_var_range, var, _scope_range, scope_kind, source)| {
+            let var_place = source.bytespan.site_id();
+            let parent_frame = if scope_kind == &ScopeKind::Fn {
+                format_ident!("__wye_frame")
+            } else if scope_kind == &ScopeKind::Local && var_place != place {
+                format_ident!("__wye_frame_{}", var.ident)
+            } else {
+                return None;
+            };
+            seen.insert((parent_frame.to_string(), var_place)).then(|| parse_quote!(
+                __wye.edge(#parent_frame, #var_place, #frame, #place);
+            ))
+        }).collect()
+    }
+
+    /// Generates statements recording `receiver`, a method call's receiver
+    /// that [is_pure_place], as its first argument, with a node of its own.
+    ///
+    /// Receivers that are places are not wrapped like other arguments,
+    /// which would move them, but are recorded by reference just before
+    /// the call. Unless they are `&mut` parameters, the call does not
+    /// record a new version of them, even if it mutates them; if they are,
+    /// the value recorded here confirms or undoes the version recorded by
+    /// the last call on them (see [wye::Logger::assign_tentatively]).
+    fn receiver(&mut self, receiver: &Expr) -> Vec<Stmt> {
+        let span = receiver.span();
+        let slot = Bytespan::new(self.source_hash, span).site_id();
+        let text = span.unwrap().source_text().unwrap_or_default();
+        self.site(slot, span, &text, expr_kind(receiver));
+        let format = match as_ident(receiver) {
+            Some(ident) => self.format(ident, parse_quote!(&(#receiver))),
+            None => capture(parse_quote!(&(#receiver))),
+        };
+        let mut stmts: Vec<Stmt> = vec![
+            parse_quote!(__wye.node(__wye_outer_frame, #slot, Some(#text.into()), #format);),
+            self.locate("__wye_outer_frame", slot, span, &text),
+        ];
+        if let Some(binder_slot) = self.mut_param_root(receiver) {
+            stmts.push(parse_quote!(__wye.observe(__wye_frame, #binder_slot, __wye_outer_frame, #slot);));
+        }
+        stmts.extend(Self::edges(&self.bindings(receiver), &format_ident!("__wye_outer_frame"), slot));
+        stmts.push(parse_quote!(__wye.push_var((__wye_outer_frame, #slot));));
+        stmts
+    }
+
+    /// Visits `expr`, which is borrowed or assigned to rather than moved,
+    /// leaving places like `self.name` in place rather than moving them
+    /// into a node; their values are recorded where they are used.
+    fn visit_place_mut(&mut self, expr: &mut Expr) {
+        if !is_pure_place(expr) {
+            self.visit_operand_mut(expr);
+        }
+    }
+
+    /// Visits `expr`, an operand of the expression being compiled, like
+    /// `f(x)` in `f(x) + 1`, and, if it gets a node of its own, records
+    /// that node as one the expression's value comes from, in place of
+    /// edges from the variables that `expr` uses; see [Parts::operand_edges].
+    fn visit_operand_mut(&mut self, expr: &mut Expr) {
+        let range = expr.span().unwrap().start()..expr.span().unwrap().end();
+        let has_node = as_ident(expr).is_none() && !is_unrecorded(expr) && !matches!(expr, Expr::ForLoop(_) | Expr::While(_) | Expr::Loop(_));
+        self.visit_expr_mut(expr);
+        if has_node {
+            self.operands.push(range);
+            *expr = parse_quote!(({
+                let __wye_ret = #expr;
+                __wye_operands.push(__wye.last_node());
+                __wye_ret
+            }));
+        }
+    }
+
+    /// Generates statements, to precede and follow the evaluation of an
+    /// expression whose `operands` got nodes of their own, that collect
+    /// those nodes and add edges from them to the node at `frame`, `place`.
+    fn operand_edges(operands: &[Range<proc_macro::LineColumn>], frame: &Ident, place: u64) -> (Option<Stmt>, Option<Stmt>) {
+        if operands.is_empty() {
+            return (None, None);
+        }
+        (
+            Some(parse_quote!(let mut __wye_operands: Vec<(u64, u64)> = vec![];)),
+            Some(parse_quote!(
+                for (__wye_operand_frame, __wye_operand_slot) in __wye_operands {
+                    __wye.edge(__wye_operand_frame, __wye_operand_slot, #frame, #place);
+                }
+            )),
+        )
+    }
+
+    /// Generates an opaque node for `expr`, which is left uninstrumented
+    /// and whose value is not recorded, with edges from the variables it
+    /// uses so that the causal path does not break where it is hidden.
+    fn opaque(&mut self, place: u64, span: Span, text: &str, var: &str, expr: &Expr) -> Expr {
+        let mut edges: Vec<Stmt> = vec![];
+        let mut seen = HashSet::new();
+        for (_var_range, var, _scope_range, scope_kind, source) in self.bindings(expr) {
+            let var_place = source.bytespan.site_id();
+            let frame = match scope_kind {
+                ScopeKind::Fn => format_ident!("__wye_frame"),
+                ScopeKind::Local => format_ident!("__wye_frame_{}", var.ident),
+                ScopeKind::Block => continue,
+            };
+            if seen.insert((frame.to_string(), var_place)) {
+                edges.push(parse_quote!(__wye.edge(#frame, #var_place, __wye_opaque_frame, #place);));
+            }
+        }
+        self.site(place, span, text, expr_kind(expr));
+        let locate = self.locate("__wye_opaque_frame", place, span, text);
+        parse_quote!(({
+            let _ = "case: opaque";
+            let __wye = get_wye();
+            let (__wye_opaque_frame, _) = __wye.frame();
+            __wye.declare_node(__wye_opaque_frame, #place);
+            #locate
+            let __wye_ret = #expr;
+            __wye.define_node(__wye_opaque_frame, #place, Some(#var.into()), "<skipped>");
+            #(#edges)*
+            __wye_ret
+        }))
+    }
+
+    /// Generates a statement, to follow `local`, a let-binding with a
+    /// destructuring pattern like `let Point{x, y} = p;`, that records each
+    /// variable it binds with edges from its initializer and binds their
+    /// `__wye_frame_{var}` variables, if it binds any.
+    fn destructure(&mut self, local: &Local) -> Option<Stmt> {
+        let (_, init) = local.init.as_ref()?;
+        let skip = local.attrs.iter().any(is_skip);
+        let bindings = self.bindings(init);
+        // initializers other than variables get nodes of their own, which
+        // are defined last, just before this statement runs:
+        let init_node: Vec<Stmt> = if as_ident(init).is_none() && !skip {
+            vec![parse_quote!(let (__wye_init_frame, __wye_init_slot) = __wye.last_node();)]
+        } else {
+            vec![]
+        };
+        self.record_binders(&local.pat, &bindings, init_node, skip)
+    }
+
+    /// Generates a statement that records each variable bound by `pat`
+    /// with edges from `bindings` and, if `init_node` binds
+    /// `__wye_init_frame` and `__wye_init_slot`, from that node, and binds
+    /// their `__wye_frame_{var}` variables, if it binds any.
+    fn record_binders(&mut self, pat: &syn::Pat, bindings: &[Binding], init_node: Vec<Stmt>, skip: bool) -> Option<Stmt> {
+        let vars = binders(pat);
+        if vars.is_empty() {
+            return None;
+        }
+        let mut stmts: Vec<Stmt> = vec![];
+        for ident in &vars {
+            let ident_str = ident.to_string();
+            let slot = Bytespan::new(self.source_hash, ident.span()).site_id();
+            self.site(slot, ident.span(), &ident_str, "Pat::Ident".into());
+            let format = if skip {
+                parse_quote!("<skipped>")
+            } else {
+                self.format(ident, parse_quote!(&#ident))
+            };
+            stmts.push(parse_quote!(__wye.node(__wye_node_frame, #slot, Some(#ident_str.into()), #format);));
+            stmts.push(self.locate("__wye_node_frame", slot, ident.span(), &ident_str));
+            stmts.extend(Self::edges(bindings, &format_ident!("__wye_node_frame"), slot));
+            if !init_node.is_empty() {
+                stmts.push(parse_quote!(__wye.edge(__wye_init_frame, __wye_init_slot, __wye_node_frame, #slot);));
+            }
+        }
+        let frames = vars.iter().map(|ident| format_ident!("__wye_frame_{}", ident));
+        let node_frames = vars.iter().map(|_| format_ident!("__wye_node_frame"));
+        Some(parse_quote!(
+            let (#(#frames,)*) = {
+                let _ = "case: destructure";
+                let __wye = get_wye();
+                #(#init_node)*
+                let (__wye_node_frame, _) = __wye.frame();
+                #(#stmts)*
+                __wye.push_frame(); __wye.pop_frame();
+                (#(#node_frames,)*)
+            };
+        ))
+    }
+
+    /// Generates statements recording `cond`, the condition of an `if` or
+    /// the scrutinee of a `match` or if-let, as a node whose address they
+    /// bind to `__wye_cond`, and returns the expression to branch on in its
+    /// place.
+    ///
+    /// Places, like `opt` or `self.state`, are recorded by reference, so
+    /// that matching on them does not move them; other conditions are
+    /// visited, and so get nodes of their own, and evaluated first.
+    fn condition(&mut self, cond: &mut Expr) -> (Vec<Stmt>, Expr) {
+        if !is_pure_place(cond) {
+            self.visit_expr_mut(cond);
+            let stmts = vec![
+                parse_quote!(let __wye_cond_value = #cond;),
+                parse_quote!(let __wye_cond = __wye.last_node();),
+            ];
+            return (stmts, parse_quote!(__wye_cond_value));
+        }
+        let span = cond.span();
+        let slot = Bytespan::new(self.source_hash, span).site_id();
+        let text = span.unwrap().source_text().unwrap_or_default();
+        self.site(slot, span, &text, expr_kind(cond));
+        let format = match as_ident(cond) {
+            Some(ident) => self.format(ident, parse_quote!(&(#cond))),
+            None => capture(parse_quote!(&(#cond))),
+        };
+        let mut stmts: Vec<Stmt> = vec![
+            parse_quote!(let (__wye_node_frame, _) = __wye.frame();),
+            parse_quote!(__wye.node(__wye_node_frame, #slot, Some(#text.into()), #format);),
+            self.locate("__wye_node_frame", slot, span, &text),
+        ];
+        stmts.extend(Self::edges(&self.bindings(cond), &format_ident!("__wye_node_frame"), slot));
+        stmts.push(parse_quote!(let __wye_cond = (__wye_node_frame, #slot);));
+        (stmts, cond.clone())
+    }
+
+    /// Visits `body`, a branch of an `if` or the body of a match arm, and
+    /// returns its tail expression, as it was before, if any; blocks get a
+    /// branch node rather than a node of their own, so only their
+    /// statements are visited.
+    fn visit_branch_mut(&mut self, body: &mut Expr) -> Option<Expr> {
+        match body {
+            Expr::Block(ExprBlock{attrs, label: None, block}) if attrs.is_empty() => {
+                let tail = match block.stmts.last() {
+                    Some(Stmt::Expr(tail)) => Some(tail.clone()),
+                    _ => None,
+                };
+                self.visit_block_mut(block);
+                tail
+            },
+            _ => {
+                let tail = body.clone();
+                self.visit_expr_mut(body);
+                Some(tail)
+            },
+        }
+    }
+
+    /// Generates a block evaluating `body`, a branch of an `if` or the body
+    /// of a match arm that the condition at `__wye_cond` chose, which
+    /// records its value as a node named `var`, located at `span`, after
+    /// running `record`, if any, to record the variables the branch binds.
+    ///
+    /// The condition gets a control edge to the branch's node and to every
+    /// node defined while `body` runs; the branch's value comes from its
+    /// tail, which is `body`'s tail expression before it was visited.
+    fn branch(&mut self, span: Span, var: &str, kind: &str, body: Expr, tail: Option<Expr>, record: Option<Stmt>) -> Expr {
+        // branches ending in a `break;` or `return x;` statement, with no
+        // tail, diverge too:
+        if tail.as_ref().is_some_and(diverges) || diverges(&body) {
+            return parse_quote!({
+                #record
+                let __wye_branch = __wye.enter_branch(__wye_cond);
+                #body
+            });
+        }
+        let slot = Bytespan::new(self.source_hash, span).site_id();
+        self.site(slot, span, var, kind.into());
+        let locate = self.locate("__wye_node_frame", slot, span, var);
+        let format = capture(parse_quote!(&__wye_ret));
+        // tails other than variables get nodes of their own, which are
+        // defined last, just after body runs:
+        let (tail_node, edges): (Vec<Stmt>, Vec<Stmt>) = match &tail {
+            Some(tail) if as_ident(tail).is_some() => {
+                (vec![], Self::edges(&self.bindings(tail), &format_ident!("__wye_node_frame"), slot))
+            },
+            Some(tail) if !is_unrecorded(tail) => (
+                vec![parse_quote!(let (__wye_tail_frame, __wye_tail_slot) = __wye.last_node();)],
+                vec![parse_quote!(__wye.edge(__wye_tail_frame, __wye_tail_slot, __wye_node_frame, #slot);)],
+            ),
+            _ => (vec![], vec![]),
+        };
+        parse_quote!({
+            #record
+            let __wye_branch = __wye.enter_branch(__wye_cond);
+            let (__wye_node_frame, _) = __wye.frame();
+            __wye.declare_node(__wye_node_frame, #slot);
+            #locate
+            let __wye_ret = #body;
+            #(#tail_node)*
+            __wye.define_node(__wye_node_frame, #slot, Some(#var.into()), #format);
+            #(#edges)*
+            __wye_ret
+        })
+    }
+
+    /// Generates a statement recording the variables that `pat`, a match
+    /// arm's or an if-let's pattern, binds, with edges from the scrutinee
+    /// at `__wye_cond`.
+    fn arm_binders(&mut self, pat: &syn::Pat) -> Option<Stmt> {
+        let init_node = vec![parse_quote!(let (__wye_init_frame, __wye_init_slot) = __wye_cond;)];
+        self.record_binders(pat, &[], init_node, false)
+    }
+
+    /// Instruments `expr_if`, recording its condition, or its scrutinee and
+    /// the variables its pattern binds if it is an if-let, and the branch it
+    /// takes, named `then` or `else`.
+    fn visit_if_mut(&mut self, expr_if: &mut ExprIf) -> Expr {
+        let ExprIf{cond, then_branch, else_branch, ..} = expr_if;
+        let (pat, cond) = match cond.as_mut() {
+            Expr::Let(ExprLet{pat, expr, ..}) => (Some(pat.clone()), expr.as_mut()),
+            cond => (None, cond),
+        };
+        let (cond_stmts, cond) = self.condition(cond);
+        let record = pat.as_ref().and_then(|pat| self.arm_binders(pat));
+        let cond = match pat {
+            Some(pat) => quote!(let #pat = #cond),
+            None => quote!(#cond),
+        };
+        let span = then_branch.span();
+        let mut body = Expr::Block(ExprBlock{attrs: vec![], label: None, block: then_branch.clone()});
+        let tail = self.visit_branch_mut(&mut body);
+        let then_branch = self.branch(span, "then", "Branch", body, tail, record);
+        let else_branch = else_branch.as_mut().map(|(else_token, body)| {
+            let span = body.span();
+            let tail = self.visit_branch_mut(body);
+            let body = self.branch(span, "else", "Branch", (**body).clone(), tail, None);
+            quote!(#else_token #body)
+        });
+        parse_quote!(({
+            let _ = "case: Expr::If";
+            let __wye = get_wye();
+            #(#cond_stmts)*
+            if #cond #then_branch #else_branch
+        }))
+    }
+
+    /// Instruments `expr_match`, recording its scrutinee, the variables that
+    /// the arm it takes binds, and that arm, named after its pattern; guards
+    /// are left uninstrumented.
+    fn visit_match_mut(&mut self, expr_match: &mut ExprMatch) -> Expr {
+        let (cond_stmts, cond) = self.condition(&mut expr_match.expr);
+        let mut arms = vec![];
+        for arm in &mut expr_match.arms {
+            let record = self.arm_binders(&arm.pat);
+            let span = arm.pat.span();
+            let text = span.unwrap().source_text().unwrap_or_default();
+            let tail = self.visit_branch_mut(&mut arm.body);
+            let body = self.branch(span, &text, "Arm", (*arm.body).clone(), tail, record);
+            arms.push(Arm{body: Box::new(body), comma: Some(Default::default()), ..arm.clone()});
+        }
+        parse_quote!(({
+            let _ = "case: Expr::Match";
+            let __wye = get_wye();
+            #(#cond_stmts)*
+            match #cond {
+                #(#arms)*
+            }
+        }))
+    }
+
+    /// Generates statements adding edges to the variables that `pat` binds
+    /// from their values in the previous iteration of the innermost loop.
+    fn carries(&self, pat: &syn::Pat) -> Vec<Stmt> {
+        binders(pat).iter().map(|ident| {
+            let frame = format_ident!("__wye_frame_{}", ident);
+            let slot = Bytespan::new(self.source_hash, ident.span()).site_id();
+            parse_quote!(__wye.carry(#frame, #slot);)
+        }).collect()
+    }
+
+    /// Instruments `expr`, a `for`, `while`, or `loop` loop, giving each of
+    /// its iterations frames of their own, and recording the iterable of a
+    /// `for` loop, the condition or scrutinee of a `while` loop, with control
+    /// edges to what each iteration it allows computes, and the variables
+    /// their patterns bind, with edges from their previous iteration's.
+    fn visit_loop_mut(&mut self, expr: &mut Expr) -> Expr {
+        let label = match expr {
+            Expr::ForLoop(ExprForLoop{label, ..}) | Expr::While(ExprWhile{label, ..}) | Expr::Loop(ExprLoop{label, ..}) => label,
+            _ => unreachable!("not a loop: {}", expr_kind(expr)),
+        };
+        let label = label.as_ref().map(|label| label.name.ident.to_string());
+        let next: Stmt = parse_quote!(__wye.next_iteration(););
+        let (case, setup, head, prologue, body) = match expr {
+            Expr::ForLoop(ExprForLoop{label, pat, expr: iter, body, ..}) => {
+                let (setup, iter) = self.condition(iter);
+                let mut prologue = vec![next];
+                prologue.extend(self.arm_binders(pat));
+                prologue.extend(self.carries(pat));
+                ("case: Expr::ForLoop", setup, quote!(#label for #pat in #iter), prologue, body)
+            },
+            Expr::While(ExprWhile{label, cond, body, ..}) => match cond.as_mut() {
+                // matching a place, like `cursor`, must not move it, so its
+                // value is recorded only by the variables the pattern binds:
+                Expr::Let(ExprLet{pat, expr: scrutinee, ..}) if is_pure_place(scrutinee) => {
+                    let bindings = self.bindings(scrutinee);
+                    let mut prologue = vec![next];
+                    prologue.extend(self.record_binders(pat, &bindings, vec![], false));
+                    prologue.extend(self.carries(pat));
+                    ("case: Expr::While", vec![], quote!(#label while let #pat = #scrutinee), prologue, body)
+                },
+                Expr::Let(ExprLet{pat, expr: scrutinee, ..}) => {
+                    let (cond_stmts, scrutinee) = self.condition(scrutinee);
+                    let mut prologue = vec![parse_quote!(let __wye_cond = __wye.last_node();)];
+                    prologue.extend(self.arm_binders(pat));
+                    prologue.extend(self.carries(pat));
+                    prologue.push(parse_quote!(let __wye_branch = __wye.enter_branch(__wye_cond);));
+                    let head = quote!(#label while let #pat = { #next #(#cond_stmts)* #scrutinee });
+                    ("case: Expr::While", vec![], head, prologue, body)
+                },
+                cond => {
+                    let (cond_stmts, cond) = self.condition(cond);
+                    let prologue = vec![
+                        parse_quote!(let __wye_cond = __wye.last_node();),
+                        parse_quote!(let __wye_branch = __wye.enter_branch(__wye_cond);),
+                    ];
+                    let head = quote!(#label while { #next #(#cond_stmts)* #cond });
+                    ("case: Expr::While", vec![], head, prologue, body)
+                },
+            },
+            Expr::Loop(ExprLoop{label, body, ..}) => ("case: Expr::Loop", vec![], quote!(#label loop), vec![next], body),
+            _ => unreachable!("not a loop: {}", expr_kind(expr)),
+        };
+        self.loop_labels.push(label);
+        self.visit_block_mut(body);
+        self.loop_labels.pop();
+        parse_quote!(({
+            let _ = #case;
+            let __wye = get_wye();
+            #(#setup)*
+            let __wye_loop = __wye.enter_loop();
+            #head {
+                #(#prologue)*
+                #body
+            }
+        }))
+    }
+
+    /// Returns the variable that `expr` assigns to, with its binding, if it
+    /// is an assignment, like `x = 1`, `total += x`, or `p.y = 2`, to a
+    /// place in a parameter or `let`-bound variable.
+    fn assigned(&self, expr: &Expr) -> Option<(Ident, Binding)> {
+        let (Expr::Assign(ExprAssign{left, ..}) | Expr::AssignOp(ExprAssignOp{left, ..})) = expr else { return None };
+        if !is_pure_place(left) {
+            return None;
+        }
+        let root = place_root(left)?;
+        let binding = self.bindings(left).into_iter().find(|(_, var, ..)| root == &var.ident)?;
+        matches!(binding.3, ScopeKind::Fn | ScopeKind::Local).then(|| (root.clone(), binding))
+    }
+
+    /// Instruments `expr`, an assignment to `var`, recording the value of
+    /// `var` after it as a new version of `var`, with edges from the
+    /// assignment's right-hand side and from the previous version, which
+    /// later uses of `var` resolve to instead; see [wye::Logger::assign].
+    fn visit_assign_mut(&mut self, expr: &mut Expr, (ident, var): (Ident, Binding)) -> Expr {
+        let (Expr::Assign(ExprAssign{left, right, ..}) | Expr::AssignOp(ExprAssignOp{left, right, ..})) = &*expr else {
+            unreachable!("not an assignment: {}", expr_kind(expr));
+        };
+        let right = (**right).clone();
+        // the other variables in the place assigned to, like `i` in
+        // `v[i] = x`, are inputs too:
+        let place_bindings = self.bindings(left).into_iter()
+            .filter(|(_, use_, ..)| ident != use_.ident)
+            .collect::<Vec<_>>();
+        let ident_str = ident.to_string();
+        let span = expr.span();
+        let slot = Bytespan::new(self.source_hash, span).site_id();
+        let text = span.unwrap().source_text().unwrap_or_default();
+        self.site(slot, span, &text, expr_kind(expr));
+        let locate = self.locate("__wye_node_frame", slot, span, &text);
+        let format = self.format(&ident, parse_quote!(&#ident));
+        let node_frame = format_ident!("__wye_node_frame");
+        // right-hand sides other than variables get nodes of their own,
+        // which are defined last, just after the assignment runs:
+        let (tail_node, edges): (Vec<Stmt>, Vec<Stmt>) = if as_ident(&right).is_some() {
+            (vec![], Self::edges(&self.bindings(&right), &node_frame, slot))
+        } else if !is_unrecorded(&right) {
+            (
+                vec![parse_quote!(let (__wye_tail_frame, __wye_tail_slot) = __wye.last_node();)],
+                vec![parse_quote!(__wye.edge(__wye_tail_frame, __wye_tail_slot, __wye_node_frame, #slot);)],
+            )
+        } else {
+            (vec![], vec![])
+        };
+        let place_edges = Self::edges(&place_bindings, &node_frame, slot);
+        let binder_slot = var.4.bytespan.site_id();
+        let binder_frame = match var.3 {
+            ScopeKind::Local => format_ident!("__wye_frame_{}", ident),
+            _ => format_ident!("__wye_frame"),
+        };
+        syn::visit_mut::visit_expr_mut(self, expr);
+        parse_quote!(({
+            let _ = "case: assign";
+            let __wye = get_wye();
+            #expr;
+            #(#tail_node)*
+            let (__wye_node_frame, _) = __wye.frame();
+            __wye.node(__wye_node_frame, #slot, Some(#ident_str.into()), #format);
+            #locate
+            #(#edges)*
+            #(#place_edges)*
+            __wye.edge(#binder_frame, #binder_slot, __wye_node_frame, #slot);
+            __wye.assign(#binder_frame, #binder_slot, __wye_node_frame, #slot);
+            __wye.push_frame(); __wye.pop_frame();
+        }))
+    }
+
+    /// Instruments `closure` like a function: each invocation gets a frame
+    /// of its own, in which its parameters are recorded, with edges from
+    /// the arguments it is called with, if it is called directly.
+    fn visit_closure_mut(&mut self, closure: &mut ExprClosure) -> Expr {
+        let span = closure.span();
+        let slot = Bytespan::new(self.source_hash, span).site_id();
+        let text = span.unwrap().source_text().unwrap_or_default();
+        self.site(slot, span, &text, expr_kind(&Expr::Closure(closure.clone())));
+        let name = match &self.function {
+            Some(function) => format!("{function}::{{closure}}"),
+            None => "{closure}".into(),
+        };
+        let mut params: Vec<Stmt> = vec![];
+        let mut frames = vec![];
+        for (index, input) in closure.inputs.iter().enumerate() {
+            for ident in binders(input) {
+                let ident_str = ident.to_string();
+                let param_slot = Bytespan::new(self.source_hash, ident.span()).site_id();
+                self.site(param_slot, ident.span(), &ident_str, "Param".into());
+                let format = self.format(&ident, parse_quote!(&#ident));
+                params.push(parse_quote!(__wye.node(__wye_closure_frame, #param_slot, Some(#ident_str.into()), #format);));
+                params.push(self.locate("__wye_closure_frame", param_slot, ident.span(), &ident_str));
+                params.push(parse_quote!(
+                    if let Some((__wye_arg_frame, __wye_arg_slot)) = __wye_closure_args.get(#index).copied().flatten() {
+                        __wye.edge(__wye_arg_frame, __wye_arg_slot, __wye_closure_frame, #param_slot);
+                    }
+                ));
+                frames.push(format_ident!("__wye_frame_{}", ident));
+            }
+        }
+        // like a function's, a block body gets no node of its own:
+        let outer_loop_labels = std::mem::take(&mut self.loop_labels);
+        match closure.body.as_mut() {
+            Expr::Block(ExprBlock{attrs, label: None, block}) if attrs.is_empty() => self.visit_block_mut(block),
+            body => self.visit_expr_mut(body),
+        }
+        self.loop_labels = outer_loop_labels;
+        let body = &closure.body;
+        let body: Expr = parse_quote!({
+            let _ = "case: Expr::Closure";
+            let __wye = get_wye();
+            let __wye_closure_args = __wye.closure_args(#slot);
+            let __wye_closure_scope = __wye.enter_closure(#name, #slot);
+            let (__wye_closure_frame, _) = __wye.frame();
+            #(#params)*
+            #(let #frames = __wye_closure_frame;)*
+            #body
+        });
+        Expr::Closure(ExprClosure{body: Box::new(body), ..closure.clone()})
+    }
+
+    /// Returns an expression for the node of the value of `expr` once it is
+    /// evaluated: the live version of a variable, or the last node, which
+    /// is `expr`'s own, unless it is left unrecorded.
+    fn value_node(&self, expr: &Expr) -> Option<Expr> {
+        if as_ident(expr).is_none() {
+            return (!is_unrecorded(expr)).then(|| parse_quote!(__wye.last_node()));
+        }
+        let (frame, source) = match self.bindings(expr).pop()? {
+            (_, var, _, ScopeKind::Local, source) => (format_ident!("__wye_frame_{}", var.ident), source),
+            (_, _, _, ScopeKind::Fn, source) => (format_ident!("__wye_frame"), source),
+            _ => return None,
+        };
+        let slot = source.bytespan.site_id();
+        Some(parse_quote!(__wye.live(#frame, #slot)))
+    }
+
+    /// Instruments `expr_return`, recording the value it returns, if any, as
+    /// its function's or closure's; see [wye::Logger::exit_return].
+    fn visit_return_mut(&mut self, expr_return: &mut ExprReturn) {
+        let node = expr_return.expr.as_deref().and_then(|value| self.value_node(value));
+        syn::visit_mut::visit_expr_return_mut(self, expr_return);
+        if let (Some(node), Some(value)) = (node, expr_return.expr.as_mut()) {
+            **value = parse_quote!({
+                let _ = "case: Expr::Return";
+                let __wye_ret = #value;
+                let __wye = get_wye();
+                let (__wye_exit_frame, __wye_exit_slot) = #node;
+                __wye.exit_return(__wye_exit_frame, __wye_exit_slot);
+                __wye_ret
+            });
+        }
+    }
+
+    /// Instruments `expr_break`, recording the value it breaks with, if
+    /// any, as that of the loop it breaks out of; see
+    /// [wye::Logger::exit_break].
+    fn visit_break_mut(&mut self, expr_break: &mut ExprBreak) {
+        // labeled blocks, which are not loops, are not counted:
+        let label = expr_break.label.as_ref().map(|label| label.ident.to_string());
+        let depth = self.loop_labels.iter().rev()
+            .position(|loop_label| label.is_none() || loop_label == &label);
+        let node = expr_break.expr.as_deref().and_then(|value| self.value_node(value));
+        syn::visit_mut::visit_expr_break_mut(self, expr_break);
+        if let (Some(depth), Some(node), Some(value)) = (depth, node, expr_break.expr.as_mut()) {
+            **value = parse_quote!({
+                let _ = "case: Expr::Break";
+                let __wye_ret = #value;
+                let __wye = get_wye();
+                let (__wye_exit_frame, __wye_exit_slot) = #node;
+                __wye.exit_break(#depth, __wye_exit_frame, __wye_exit_slot);
+                __wye_ret
+            });
+        }
+    }
+
+    /// Instruments `expr_try`, a `?` applied to the operand whose value is
+    /// at `node`, recording the operand as its function's or closure's
+    /// value until it turns out not to short-circuit, and then recording
+    /// its result; see [wye::Logger::exit_try].
+    fn visit_try_mut(&mut self, expr_try: &mut ExprTry, node: Expr, ascribed: Option<TokenStream>) -> Expr {
+        let span = expr_try.span();
+        let slot = Bytespan::new(self.source_hash, span).site_id();
+        let text = span.unwrap().source_text().unwrap_or_default();
+        self.site(slot, span, &text, expr_kind(&Expr::Try(expr_try.clone())));
+        let locate = self.locate("__wye_node_frame", slot, span, &text);
+        let format = capture(parse_quote!(&__wye_ret));
+        self.visit_expr_mut(&mut expr_try.expr);
+        let ExprTry{expr: operand, question_token, ..} = expr_try;
+        parse_quote!(({
+            let _ = "case: Expr::Try";
+            let __wye = get_wye();
+            let (__wye_node_frame, _) = __wye.frame();
+            let __wye_try = #operand;
+            let (__wye_exit_frame, __wye_exit_slot) = #node;
+            __wye.exit_try(__wye_exit_frame, __wye_exit_slot);
+            let __wye_ret #ascribed = __wye_try #question_token;
+            __wye.resume();
+            __wye.declare_node(__wye_node_frame, #slot);
+            #locate
+            __wye.define_node(__wye_node_frame, #slot, None::<String>, #format);
+            __wye.edge(__wye_exit_frame, __wye_exit_slot, __wye_node_frame, #slot);
+            __wye_ret
+        }))
+    }
+
+    /// Returns the arguments of `expr`, a call, that it may mutate, by
+    /// position, with the variables at their roots and their bindings:
+    /// `&mut` borrows of places, variables passed along, like `&mut`
+    /// parameters, and receivers that are places.
+    ///
+    /// Those flagged are assumed to be mutated even by callees that do not
+    /// say: `&mut` borrows, and receivers rooted in the `&mut` parameters
+    /// of the function being instrumented. Which methods need `&mut` is
+    /// not known here, so the latter come with the slots of their nodes,
+    /// recorded before the call, against which the assumption is checked
+    /// once they are next recorded.
+    fn mutable_args(&self, expr: &Expr) -> Vec<(usize, Ident, Binding, bool, Option<u64>)> {
+        let args: Vec<(&Expr, bool)> = match expr {
+            Expr::Call(call) => call.args.iter().map(|arg| (arg, false)).collect(),
+            Expr::MethodCall(call) => [(&*call.receiver, true)].into_iter()
+                .chain(call.args.iter().map(|arg| (arg, false)))
+                .collect(),
+            _ => vec![],
+        };
+        let mut mutable = vec![];
+        for (index, (arg, is_receiver)) in args.into_iter().enumerate() {
+            let (root, assumed) = match arg {
+                Expr::Reference(ExprReference{mutability: Some(_), expr, ..}) if is_pure_place(expr) => (place_root(expr), true),
+                _ if is_receiver && is_pure_place(arg) => (place_root(arg), false),
+                _ => (as_ident(arg), false),
+            };
+            let Some(root) = root else { continue };
+            let binding = self.bindings(arg).into_iter()
+                .find(|(_, var, _, scope_kind, _)| root == &var.ident && *scope_kind != ScopeKind::Block);
+            if let Some(binding) = binding {
+                let tentative = (!assumed && is_receiver && self.mut_params.contains(&binding.4.bytespan.site_id()))
+                    .then(|| Bytespan::new(self.source_hash, arg.span()).site_id());
+                mutable.push((index, root.clone(), binding, assumed || tentative.is_some(), tentative));
+            }
+        }
+        mutable
+    }
+
+    /// Returns the slot of the binder of the `&mut` parameter at the root of
+    /// `place`, a place that [is_pure_place], if it is rooted in one.
+    fn mut_param_root(&self, place: &Expr) -> Option<u64> {
+        let root = place_root(place)?;
+        self.bindings(place).into_iter()
+            .find(|(_, var, _, scope_kind, _)| root == &var.ident && *scope_kind != ScopeKind::Block)
+            .map(|(.., source)| source.bytespan.site_id())
+            .filter(|slot| self.mut_params.contains(slot))
+    }
+
+    /// Generates statements, to follow the call at `__wye_outer_frame`,
+    /// `place`, that make the nodes of the values it left in `mutable`, its
+    /// arguments that it may mutate, the live versions of the variables
+    /// they borrow: those the callee recorded with [wye::Logger::mutated],
+    /// or else the call itself, if they are assumed to be mutated and the
+    /// callee is not instrumented, tentatively for receivers.
+    fn mutations(mutable: &[(usize, Ident, Binding, bool, Option<u64>)], place: u64) -> Vec<Stmt> {
+        if mutable.is_empty() {
+            return vec![];
+        }
+        let mut stmts: Vec<Stmt> = vec![parse_quote!(let __wye_mutated = __wye.take_mutated();)];
+        for (index, ident, (.., scope_kind, source), assumed, tentative) in mutable {
+            let binder_frame = match scope_kind {
+                ScopeKind::Local => format_ident!("__wye_frame_{}", ident),
+                _ => format_ident!("__wye_frame"),
+            };
+            let binder_slot = source.bytespan.site_id();
+            let assign: Expr = match tentative {
+                Some(before) => parse_quote!(__wye.assign_tentatively(#binder_frame, #binder_slot, __wye_outer_frame, #place, __wye_outer_frame, #before)),
+                None => parse_quote!(__wye.assign(#binder_frame, #binder_slot, __wye_outer_frame, #place)),
+            };
+            stmts.push(parse_quote!(
+                match __wye_mutated.iter().find(|(__wye_index, _)| *__wye_index == #index) {
+                    Some((_, (__wye_mut_frame, __wye_mut_slot))) => __wye.assign(#binder_frame, #binder_slot, *__wye_mut_frame, *__wye_mut_slot),
+                    None if #assumed && __wye_returned.is_none() => #assign,
+                    None => {},
+                }
+            ));
+        }
+        stmts
+    }
+
+    /// Generates a node for `ident`, a call's argument `expr` that is a
+    /// free variable, like the caller's variables in a [wyre] invocation,
+    /// where it is used, and pushes it as the argument.
+    fn free_arg(&mut self, ident: &Ident, expr: &Expr) -> Expr {
+        let ident_str = ident.to_string();
+        let slot = Bytespan::new(self.source_hash, ident.span()).site_id();
+        self.site(slot, ident.span(), &ident_str, expr_kind(expr));
+        let locate = self.locate("__wye_node_frame", slot, ident.span(), &ident_str);
+        let format = self.format(ident, parse_quote!(&#ident));
+        parse_quote!(({
+            let (__wye_node_frame, _) = __wye.frame();
+            __wye.node(__wye_node_frame, #slot, Some(#ident_str.into()), #format);
+            #locate
+            let __wye_ret = #expr;
+            __wye.push_var((__wye_node_frame, #slot));
+            __wye_ret
+        }))
+    }
+
+    /// Generates a node for `expr`, a call's argument that [is_place_arg]
+    /// but is not a variable, like `&aa[..]` or `pair.0`, labelled with its
+    /// source, with edges from the variables it uses, and pushes it as the
+    /// argument.
+    ///
+    /// Its parts are left in place rather than moved into nodes of their
+    /// own, which would not compile for unsized places like `aa[..]`.
+    fn place_arg(&mut self, expr: &Expr) -> Expr {
+        let span = expr.span();
+        let slot = Bytespan::new(self.source_hash, span).site_id();
+        let text = span.unwrap().source_text().unwrap_or_default();
+        self.site(slot, span, &text, expr_kind(expr));
+        let locate = self.locate("__wye_node_frame", slot, span, &text);
+        let edges = Self::edges(&self.bindings(expr), &format_ident!("__wye_node_frame"), slot);
+        let format = capture(parse_quote!(&__wye_ret));
+        parse_quote!(({
+            let _ = "case: place arg";
+            let (__wye_node_frame, _) = __wye.frame();
+            __wye.declare_node(__wye_node_frame, #slot);
+            #locate
+            let __wye_ret = #expr;
+            __wye.define_node(__wye_node_frame, #slot, Some(#text.into()), #format);
+            #(#edges)*
+            __wye.push_var((__wye_node_frame, #slot));
+            __wye_ret
+        }))
+    }
+
     fn visit_expr_call_arg_mut(&mut self, expr: &mut Expr) {
         let expr_clone = expr.clone();
+        if as_ident(expr).is_none() && is_place_arg(expr) {
+            *expr = self.place_arg(expr);
+            return;
+        }
         self.visit_expr_mut(expr);
         if let Some(ident) = as_ident(&expr_clone) {
             let mut bindings = self.bindings(expr);
-            let (_var_range, _var, _scope_range, scope_kind, source) = bindings
-                .pop()
-                .unwrap_or_else(|| panic!("missing bindings for expr: {expr:?}"));
+            let Some((_var_range, _var, _scope_range, scope_kind, source)) = bindings.pop() else {
+                *expr = self.free_arg(ident, expr);
+                return;
+            };
             if scope_kind == ScopeKind::Local {
                 let frame_ident = format_ident!("__wye_frame_{}", ident);
-                let var_place = hash(source.bytespan);
+                let var_place = source.bytespan.site_id();
                 *expr = parse_quote!(({
                     let __wye_ret = #expr;
                     __wye.push_var((#frame_ident, #var_place));
@@ -323,12 +1427,25 @@ impl<'ast> Parts<'ast> {
                     __wye_ret
                 }));
             } else {
+                let var_place = source.bytespan.site_id();
                 *expr = parse_quote!(({
                     let __wye_ret = #expr;
-                    __wye.push_var(__wye.last_node());
+                    __wye.push_var((__wye_frame, #var_place));
                     __wye_ret
                 }));
             }
+        } else if let Expr::Closure(_) = expr {
+            // the closure stays in tail position, where its expected type
+            // reaches it, and is linked to the call it is passed to:
+            let slot = Bytespan::new(self.source_hash, expr_clone.span()).site_id();
+            let site = self.call.map(|place| -> Stmt {
+                parse_quote!(__wye.closure_site(#slot, (__wye_outer_frame, #place));)
+            });
+            *expr = parse_quote!({
+                __wye.push_lit();
+                #site
+                #expr
+            });
         } else {
             *expr = parse_quote!(({
                 let __wye_ret = #expr;
@@ -338,41 +1455,240 @@ impl<'ast> Parts<'ast> {
         }
     }
 
+    /// Instruments a function or method, named after the modules and owner
+    /// it is in, if any, like `Counter::incr` or `net::connect`, unless the
+    /// [Filters] exclude that name.
+    fn visit_fn_mut(&mut self, sig: &mut Signature, block: &mut Block) {
+        // sig_clone keeps any #[wye(skip)] attributes for visit_fn_block_mut:
+        let sig_clone = sig.clone();
+        for input in &mut sig.inputs {
+            match input {
+                syn::FnArg::Typed(pat_type) => take_skip(&mut pat_type.attrs),
+                syn::FnArg::Receiver(receiver) => take_skip(&mut receiver.attrs),
+            };
+        }
+        self.visit_signature_mut(sig);
+        let name = self.modules.iter()
+            .chain(&self.owner)
+            .map(|prefix| format!("{prefix}::"))
+            .chain([sig_clone.ident.to_string()])
+            .collect::<String>();
+        if !self.filters.admits(&name) {
+            return;
+        }
+        // const fns run at compile time, where nothing can be recorded, and
+        // async fns interleave their frames with those of other tasks:
+        if let Some(constness) = sig.constness {
+            self.unsupported(constness.span, "`const fn`s");
+            return;
+        }
+        if let Some(asyncness) = sig.asyncness {
+            self.unsupported(asyncness.span, "`async fn`s");
+            return;
+        }
+        let outer_function = self.function.replace(name);
+        let mut_params = mut_params(&sig_clone).iter()
+            .map(|(_, ident)| Bytespan::new(self.source_hash, ident.span()).site_id())
+            .collect();
+        let outer_mut_params = std::mem::replace(&mut self.mut_params, mut_params);
+        let outer_loop_labels = std::mem::take(&mut self.loop_labels);
+        self.visit_fn_block_mut(&sig_clone, block);
+        self.loop_labels = outer_loop_labels;
+        self.mut_params = outer_mut_params;
+        self.function = outer_function;
+    }
+
     fn visit_fn_block_mut(&mut self, sig: &Signature, node: &mut Block) {
         self.visit_block_mut(node);
+        // a variable returned as the tail is the function's last node, in
+        // whichever version is live when it returns:
+        if let Some(Stmt::Expr(tail)) = node.stmts.last_mut() {
+            if let Some(live) = as_ident(tail).and_then(|_| self.value_node(tail)) {
+                *tail = parse_quote!({
+                    __wye.set_last_node(#live);
+                    #tail
+                });
+            }
+        }
+        // `&mut` parameters and receivers are recorded again on exit, with
+        // edges from their live versions, unless the result may borrow
+        // from them:
+        let mutated = mut_params(sig);
+        if !mutated.is_empty() && !may_borrow(&sig.output) {
+            let mut exits: Vec<Stmt> = vec![];
+            for (index, ident) in &mutated {
+                let ident_str = ident.to_string();
+                let slot = Bytespan::new(self.source_hash, ident.span()).site_id();
+                let format = self.format(ident, parse_quote!(&#ident));
+                exits.push(parse_quote!(__wye.node(__wye_exit_frame, #slot, Some(#ident_str.into()), #format);));
+                exits.push(self.locate("__wye_exit_frame", slot, ident.span(), &ident_str));
+                exits.push(parse_quote!(__wye.observe(__wye_frame, #slot, __wye_exit_frame, #slot);));
+                exits.push(parse_quote!(__wye.edge(__wye_frame, #slot, __wye_exit_frame, #slot);));
+                exits.push(parse_quote!(__wye.mutated(#index, __wye_exit_frame, #slot);));
+            }
+            let stmts = std::mem::take(&mut node.stmts);
+            let body: Block = parse_quote!({
+                let __wye_ret = { #(#stmts)* };
+                let __wye_ret_node = __wye.last_node();
+                __wye.push_frame(); __wye.pop_frame();
+                let (__wye_exit_frame, _) = __wye.frame();
+                #(#exits)*
+                __wye.set_last_node(__wye_ret_node);
+                __wye_ret
+            });
+            node.stmts = body.stmts;
+        }
         for (input_slot, input) in sig.inputs.iter().enumerate().rev() {
-            if let syn::FnArg::Typed(syn::PatType{pat, ..}) = input {
-                if let syn::Pat::Ident(syn::PatIdent{ident, ..}) = pat.as_ref() {
-                    let ident_str = ident.to_string();
-                    let bytespan = Bytespan::new(self.source_hash, ident.span().unwrap().into());
-                    let slot = hash(&bytespan);
-                    node.stmts.insert(0, parse_quote!(
-                        if let Some((__wye_arg_frame, __wye_arg_slot)) = __wye_frame_args.get(#input_slot).copied().flatten() {
-                            if __wye_arg_frame != __wye_frame || __wye_arg_slot != #slot {
-                                __wye.edge(__wye_arg_frame, __wye_arg_slot, __wye_frame, #slot);
-                            }
+            // each variable bound by a destructuring parameter, like `(a, b)`,
+            // gets a node of its own, with an edge from the argument:
+            let (attrs, params) = match input {
+                syn::FnArg::Typed(syn::PatType{attrs, pat, ..}) => (attrs, binders(pat)),
+                syn::FnArg::Receiver(Receiver{attrs, self_token, ..}) => {
+                    (attrs, vec![Ident::new("self", self_token.span)])
+                },
+            };
+            for ident in params.into_iter().rev() {
+                let ident_str = ident.to_string();
+                let bytespan = Bytespan::new(self.source_hash, ident.span().unwrap().into());
+                let slot = bytespan.site_id();
+                self.site(slot, ident.span(), &ident_str, "Param".into());
+                let format = if attrs.iter().any(is_skip) {
+                    parse_quote!("<skipped>")
+                } else {
+                    self.format(&ident, parse_quote!(&#ident))
+                };
+                node.stmts.insert(0, self.locate("__wye_frame", slot, ident.span(), &ident_str));
+                node.stmts.insert(0, parse_quote!(
+                    if let Some((__wye_arg_frame, __wye_arg_slot)) = __wye_frame_args.get(#input_slot).copied().flatten() {
+                        if __wye_arg_frame != __wye_frame || __wye_arg_slot != #slot {
+                            __wye.edge(__wye_arg_frame, __wye_arg_slot, __wye_frame, #slot);
                         }
-                    ));
-                    node.stmts.insert(0, parse_quote!(
-                        __wye.node(__wye_frame, #slot, Some(#ident_str.into()), format!("{:?}", &#ident));
-                    ));
-                }
+                    }
+                ));
+                node.stmts.insert(0, parse_quote!(
+                    __wye.node(__wye_frame, #slot, Some(#ident_str.into()), #format);
+                ));
             }
         }
+        let fn_name = self.function.clone().unwrap_or_else(|| sig.ident.to_string());
+        node.stmts.insert(0, parse_quote!(let __wye_fn_scope = __wye.enter_fn(#fn_name);));
         node.stmts.insert(0, parse_quote!(let (__wye_frame, __wye_frame_args) = __wye.frame();));
         node.stmts.insert(0, parse_quote!(let __wye = get_wye();));
     }
 
     fn compile(&mut self, stmt_hack: Option<u64>, expr: &mut Expr) {
+        // the ascribed type is the expected type of expr, not of its
+        // sub-expressions; __wye_ret needs it for inference to succeed when
+        // expr is generic in its result, like `iter.sum()` or `x.into()`:
+        let ascription = self.ascription.take();
+        let ascribed = ascription.as_ref().map(|ty| quote!(: #ty));
+        if expr_attrs_mut(expr).is_some_and(take_skip) {
+            let place = Bytespan::new(self.source_hash, expr.span()).site_id();
+            let text = expr.span().unwrap().source_text().unwrap_or_default();
+            *expr = self.opaque(place, expr.span(), &text, &text, expr);
+            return;
+        }
+        match expr {
+            Expr::If(expr_if) => {
+                let instrumented = self.visit_if_mut(expr_if);
+                *expr = instrumented;
+                return;
+            },
+            Expr::Match(expr_match) => {
+                let instrumented = self.visit_match_mut(expr_match);
+                *expr = instrumented;
+                return;
+            },
+            Expr::ForLoop(_) | Expr::While(_) | Expr::Loop(_) => {
+                let instrumented = self.visit_loop_mut(expr);
+                *expr = instrumented;
+                return;
+            },
+            Expr::Async(ExprAsync{async_token, ..}) => {
+                self.unsupported(async_token.span, "`async` blocks");
+                return;
+            },
+            Expr::Closure(ExprClosure{asyncness: Some(async_token), ..}) => {
+                self.unsupported(async_token.span, "`async` closures");
+                return;
+            },
+            Expr::Closure(closure) => {
+                let instrumented = self.visit_closure_mut(closure);
+                *expr = instrumented;
+                return;
+            },
+            Expr::Return(expr_return) => {
+                self.visit_return_mut(expr_return);
+                return;
+            },
+            Expr::Break(expr_break) => {
+                self.visit_break_mut(expr_break);
+                return;
+            },
+            // macros wye does not recognize may expand to anything, so they
+            // are left as they are, with edges from the variables they use:
+            Expr::Macro(ExprMacro{mac, ..}) if MacroArgs::parse(mac).is_none() => {
+                let place = Bytespan::new(self.source_hash, expr.span()).site_id();
+                let text = expr.span().unwrap().source_text().unwrap_or_default();
+                *expr = self.opaque(place, expr.span(), &text, &text, expr);
+                return;
+            },
+            Expr::Try(expr_try) => {
+                if let Some(node) = self.value_node(&expr_try.expr) {
+                    let instrumented = self.visit_try_mut(expr_try, node, ascribed);
+                    *expr = instrumented;
+                    return;
+                }
+            },
+            _ => {},
+        }
+        if let Some(var) = self.assigned(expr) {
+            let instrumented = self.visit_assign_mut(expr, var);
+            *expr = instrumented;
+            return;
+        }
         let expr_clone = expr.clone();
         let expr_source = expr.span().unwrap().source_text();
         let bindings = self.bindings(expr);
 
         let place = stmt_hack.unwrap_or_else(|| {
-            hash(Bytespan::new(self.source_hash, expr.span().unwrap().into()))
+            Bytespan::new(self.source_hash, expr.span().unwrap().into()).site_id()
         });
+        // like parameters, let-bindings are located at their binder, which
+        // is also what their stmt_hack place is computed from:
+        let (place_span, place_text) = match expr {
+            Expr::Let(ExprLet{pat: syn::Pat::Ident(ident), ..}) if stmt_hack.is_some() => {
+                (ident.ident.span(), ident.ident.to_string())
+            },
+            _ => (expr.span(), expr_source.clone().unwrap_or_default()),
+        };
+        if as_ident(expr).is_none() && !is_unrecorded(expr) {
+            self.site(place, place_span, &place_text, expr_kind(expr));
+        }
+        let locate_outer = self.locate("__wye_outer_frame", place, place_span, &place_text);
+        let locate = self.locate("__wye_node_frame", place, place_span, &place_text);
+        let receiver: Vec<Stmt> = match expr {
+            Expr::MethodCall(call) if is_pure_place(&call.receiver) => self.receiver(&call.receiver),
+            _ => vec![],
+        };
+        let mutations = Self::mutations(&self.mutable_args(expr), place);
 
+        // closures passed to this call, if it is one, are linked to it:
+        let call = matches!(expr, Expr::Call(_) | Expr::MethodCall(_)).then_some(place);
+        let outer_call = std::mem::replace(&mut self.call, call);
+        let outer_operands = std::mem::take(&mut self.operands);
         syn::visit_mut::visit_expr_mut(self, expr);
+        let operands = std::mem::replace(&mut self.operands, outer_operands);
+        self.call = outer_call;
+
+        let capture_ret = capture(parse_quote!(&__wye_ret));
+        let format = match expr {
+            Expr::Let(ExprLet{pat: syn::Pat::Ident(ident), ..}) => {
+                let ident = ident.ident.clone();
+                self.format(&ident, parse_quote!(&__wye_ret))
+            },
+            _ => capture(parse_quote!(&__wye_ret)),
+        };
 
         let mvar: Expr = if let Some(ident) = as_ident(expr) {
             let ident = ident.to_string();
@@ -391,49 +1707,52 @@ impl<'ast> Parts<'ast> {
             parse_quote!(None::<String>)
         };
 
+        // __wye_frame is the enclosing #[wye] function's frame, in which
+        // its parameters were recorded; the node for expr is recorded in
+        // node_frame:
+        let node_frame = match &expr_clone {
+            Expr::Call(_) | Expr::MethodCall(_) | Expr::Macro(_) => format_ident!("__wye_outer_frame"),
+            Expr::Let(ExprLet{pat: syn::Pat::Ident(_), ..}) if stmt_hack.is_none() => format_ident!("__wye_outer_frame"),
+            _ => format_ident!("__wye_node_frame"),
+        };
         let edges: Vec<Stmt> = if as_ident(expr).is_some() {
             vec![]
         } else {
-            bindings.iter().filter_map(|(_var_range, var, _scope_range, scope_kind, source)| {
-                if scope_kind == &ScopeKind::Fn {
-                    let bytespan = &source.bytespan;
-                    let var_place = hash(&bytespan);
-                    return Some(parse_quote!(
-                        __wye.edge(__wye_frame, #var_place, __wye_frame, #place);
-                    ))
-                } else if scope_kind == &ScopeKind::Local {
-                    let bytespan = &source.bytespan;
-                    let var_place = hash(&bytespan);
-                    let parent_frame = format_ident!("__wye_frame_{}", var.ident);
-                    if stmt_hack.is_none() {
-                        return Some(parse_quote!(
-                            __wye.edge(#parent_frame, #var_place, __wye_outer_frame, #place);
-                        ));
-                    } else {
-                        return Some(parse_quote!(
-                            __wye.edge(__wye_expr_frame, __wye_expr_place, __wye_frame, #var_place);
-                        ))
-                    }
-                }
-                None
-            }).collect::<Vec<_>>()
+            Self::edges(&outside(bindings.clone(), &operands), &node_frame, place)
         };
+        let (collect_operands, operand_edges) = Self::operand_edges(&operands, &node_frame, place);
 
         match expr_clone {
-            Expr::Call(_) => {
+            // the callee's last node, if it is instrumented, and otherwise
+            // the arguments, are what the call's value comes from:
+            Expr::Call(_) | Expr::MethodCall(_) => {
+                let case = format!("case: Expr::{}", expr_kind(&expr_clone).trim_start_matches("Expr::"));
                 *expr = parse_quote!(({
-                    let _ = "case: Expr::Call";
+                    let _ = #case;
                     let _ = #expr_source;
                     let __wye = get_wye();
                     let (__wye_outer_frame, _) = __wye.frame();
                     __wye.declare_node(__wye_outer_frame, #place);
+                    #locate_outer
                     __wye.push_frame();
-                    let __wye_ret = #expr;
+                    #(#receiver)*
+                    #collect_operands
+                    let __wye_ret #ascribed = #expr;
+                    let (_, __wye_args) = __wye.frame();
                     __wye.pop_frame();
-                    let (__wye_inner_frame, __wye_inner_slot) = __wye.last_node();
-                    __wye.define_node(__wye_outer_frame, #place, Some(#expr_source.into()), format!("{:?}", &__wye_ret));
-                    __wye.edge(__wye_inner_frame, __wye_inner_slot, __wye_outer_frame, #place);
+                    let __wye_returned = __wye.take_returned();
+                    __wye.define_node(__wye_outer_frame, #place, Some(#expr_source.into()), #capture_ret);
+                    if let Some(((__wye_inner_frame, __wye_inner_slot), __wye_exit)) = __wye_returned {
+                        __wye.exit_edge(__wye_inner_frame, __wye_inner_slot, __wye_outer_frame, #place, __wye_exit);
+                    }
+                    #operand_edges
                     #(#edges)*;
+                    if __wye_returned.is_none() {
+                        for (__wye_arg_frame, __wye_arg_slot) in __wye_args.into_iter().flatten() {
+                            __wye.link(__wye_arg_frame, __wye_arg_slot, __wye_outer_frame, #place);
+                        }
+                    }
+                    #(#mutations)*
                     __wye_ret
                 }));
             },
@@ -444,8 +1763,11 @@ impl<'ast> Parts<'ast> {
                     let __wye = get_wye();
                     let (__wye_outer_frame, _) = __wye.frame();
                     __wye.declare_node(__wye_outer_frame, #place);
-                    let __wye_ret = #expr;
-                    __wye.define_node(__wye_outer_frame, #place, Some(#expr_source.into()), format!("{:?}", &__wye_ret));
+                    #locate_outer
+                    #collect_operands
+                    let __wye_ret #ascribed = #expr;
+                    __wye.define_node(__wye_outer_frame, #place, Some(#expr_source.into()), #capture_ret);
+                    #operand_edges
                     #(#edges)*;
                     __wye_ret
                 }));
@@ -458,28 +1780,62 @@ impl<'ast> Parts<'ast> {
                         let __wye = get_wye();
                         let (__wye_outer_frame, _) = __wye.frame();
                         __wye.declare_node(__wye_outer_frame, #place);
+                        #locate_outer
                         __wye.push_frame();
                         let __wye_ret = #inner_expr;
                         __wye.pop_frame();
-                        __wye.define_node(__wye_outer_frame, #place, #mvar, format!("{:?}", &__wye_ret));
+                        __wye.define_node(__wye_outer_frame, #place, #mvar, #format);
                         #(#edges)*;
                         __wye_ret
                     })
                 );
             },
             Expr::Let(syn::ExprLet{pat: syn::Pat::Ident(_ident), expr: mut inner_expr, ..}) => {
-                syn::visit_mut::visit_expr_mut(self, inner_expr.as_mut());
-                // only emit edges if inner_expr will have defined a last_node:
+                // calls, and the branches of ifs and matches, get nodes of
+                // their own, from which the binding's value comes:
+                let is_call = matches!(&*inner_expr, Expr::Call(_) | Expr::MethodCall(_) | Expr::If(_) | Expr::Match(_) | Expr::Try(_));
+                // a `loop`'s value is the value it breaks with:
+                let broken: Option<Stmt> = matches!(&*inner_expr, Expr::Loop(_)).then(|| parse_quote!(
+                    if let Some(((__wye_inner_frame, __wye_inner_slot), __wye_exit)) = __wye.take_broken() {
+                        __wye.exit_edge(__wye_inner_frame, __wye_inner_slot, __wye_node_frame, #place, __wye_exit);
+                    }
+                ));
+                // loops and closures are instrumented in place:
+                let is_block_like = matches!(&*inner_expr, Expr::ForLoop(_) | Expr::While(_) | Expr::Loop(_) | Expr::Closure(_));
+                // as are macros that wye does not recognize, which get
+                // opaque nodes:
+                let is_opaque = matches!(&*inner_expr, Expr::Macro(ExprMacro{mac, ..}) if MacroArgs::parse(mac).is_none());
+                // as is skipped code:
+                let is_skipped = expr_attrs_mut(&mut inner_expr).is_some_and(|attrs| attrs.iter().any(is_skip));
+                let has_node = is_call || is_opaque || is_skipped;
+                let outer_operands = std::mem::take(&mut self.operands);
+                if has_node || is_block_like {
+                    self.ascription = ascription;
+                    self.compile(None, &mut inner_expr);
+                } else {
+                    syn::visit_mut::visit_expr_mut(self, inner_expr.as_mut());
+                }
+                let operands = std::mem::replace(&mut self.operands, outer_operands);
+                // the variables that a node of inner_expr's own uses are
+                // linked to that node rather than to the binding:
+                let edges = if has_node {
+                    vec![]
+                } else {
+                    Self::edges(&outside(bindings, &operands), &node_frame, place)
+                };
+                let (collect_operands, operand_edges) = Self::operand_edges(&operands, &node_frame, place);
+                // only emit edges if inner_expr will have defined a last_node:
                 if matches!(&*inner_expr, Expr::Lit(_)) {
                     *expr = parse_quote!(
                         (get_wye().frame().0, {
                             let _ = "case: stmt_hack.lit";
                             let _ = #expr_source;
                             let __wye = get_wye();
-                            let (__wye_frame, _) = __wye.frame();
-                            __wye.declare_node(__wye_frame, #place);
-                            let __wye_ret = #inner_expr;
-                            __wye.define_node(__wye_frame, #place, #mvar, format!("{:?}", &__wye_ret));
+                            let (__wye_node_frame, _) = __wye.frame();
+                            __wye.declare_node(__wye_node_frame, #place);
+                            #locate
+                            let __wye_ret #ascribed = #inner_expr;
+                            __wye.define_node(__wye_node_frame, #place, #mvar, #format);
                             __wye.push_frame(); __wye.pop_frame();
                             __wye_ret
                         })
@@ -490,11 +1846,18 @@ impl<'ast> Parts<'ast> {
                             let _ = "case: stmt_hack";
                             let _ = #expr_source;
                             let __wye = get_wye();
-                            let (__wye_frame, _) = __wye.frame();
-                            __wye.declare_node(__wye_frame, #place);
-                            let __wye_ret = #inner_expr;
-                            let (__wye_expr_frame, __wye_expr_place) = __wye.last_node();
-                            __wye.define_node(__wye_frame, #place, #mvar, format!("{:?}", &__wye_ret));
+                            let (__wye_node_frame, _) = __wye.frame();
+                            __wye.declare_node(__wye_node_frame, #place);
+                            #locate
+                            #collect_operands
+                            let __wye_ret #ascribed = #inner_expr;
+                            let __wye_inner_node = #has_node.then(|| __wye.last_node());
+                            __wye.define_node(__wye_node_frame, #place, #mvar, #format);
+                            if let Some((__wye_inner_frame, __wye_inner_slot)) = __wye_inner_node {
+                                __wye.edge(__wye_inner_frame, __wye_inner_slot, __wye_node_frame, #place);
+                            }
+                            #operand_edges
+                            #broken
                             #(#edges)*;
                             __wye.push_frame(); __wye.pop_frame();
                             __wye_ret
@@ -502,15 +1865,19 @@ impl<'ast> Parts<'ast> {
                     );
                 }
             },
+            _ if is_unrecorded(&expr_clone) => {},
             _ if as_ident(&expr_clone).is_none() => {
                 *expr = parse_quote!(({
                     let _ = "case: non-ident";
                     let _ = #expr_source;
                     let __wye = get_wye();
-                    let (__wye_frame, _) = __wye.frame();
-                    __wye.declare_node(__wye_frame, #place);
-                    let __wye_ret = #expr;
-                    __wye.define_node(__wye_frame, #place, #mvar, format!("{:?}", &__wye_ret));
+                    let (__wye_node_frame, _) = __wye.frame();
+                    __wye.declare_node(__wye_node_frame, #place);
+                    #locate
+                    #collect_operands
+                    let __wye_ret #ascribed = #expr;
+                    __wye.define_node(__wye_node_frame, #place, #mvar, #format);
+                    #operand_edges
                     #(#edges)*;
                     __wye_ret
                 }));
@@ -523,26 +1890,105 @@ impl<'ast> Parts<'ast> {
 impl<'ast> VisitMut for Parts<'ast> {
 
     fn visit_item_fn_mut(&mut self, node: &mut ItemFn) {
+        if leave_item(&mut node.attrs) {
+            return;
+        }
         // See syn::visit_mut::visit_item_fn_mut(self, node);
-        let sig_clone = node.sig.clone();
         for it in &mut node.attrs {
             self.visit_attribute_mut(it);
         }
         self.visit_visibility_mut(&mut node.vis);
-        self.visit_signature_mut(&mut node.sig);
-        self.visit_fn_block_mut(&sig_clone, &mut *node.block);
+        self.visit_fn_mut(&mut node.sig, &mut node.block);
+    }
+
+    fn visit_item_impl_mut(&mut self, node: &mut ItemImpl) {
+        if leave_item(&mut node.attrs) {
+            return;
+        }
+        let self_ty = &node.self_ty;
+        let owner = self_ty.span().unwrap().source_text().unwrap_or_else(|| self_ty.to_token_stream().to_string());
+        let outer_owner = self.owner.replace(owner);
+        let outer_implicit = std::mem::replace(&mut self.implicit, true);
+        syn::visit_mut::visit_item_impl_mut(self, node);
+        self.implicit = outer_implicit;
+        self.owner = outer_owner;
+    }
+
+    fn visit_item_trait_mut(&mut self, node: &mut ItemTrait) {
+        if leave_item(&mut node.attrs) {
+            return;
+        }
+        let outer_owner = self.owner.replace(node.ident.to_string());
+        let outer_implicit = std::mem::replace(&mut self.implicit, true);
+        syn::visit_mut::visit_item_trait_mut(self, node);
+        self.implicit = outer_implicit;
+        self.owner = outer_owner;
+    }
+
+    fn visit_impl_item_method_mut(&mut self, node: &mut ImplItemMethod) {
+        if leave_item(&mut node.attrs) {
+            return;
+        }
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_visibility_mut(&mut node.vis);
+        self.visit_fn_mut(&mut node.sig, &mut node.block);
+    }
+
+    fn visit_trait_item_method_mut(&mut self, node: &mut TraitItemMethod) {
+        if leave_item(&mut node.attrs) {
+            return;
+        }
+        // only default methods have a body to instrument:
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        if let Some(block) = &mut node.default {
+            self.visit_fn_mut(&mut node.sig, block);
+        }
+    }
+
+    fn visit_item_mod_mut(&mut self, node: &mut ItemMod) {
+        if leave_item(&mut node.attrs) {
+            return;
+        }
+        let Some((_, items)) = &mut node.content else { return };
+        self.modules.push(node.ident.to_string());
+        let outer_implicit = std::mem::replace(&mut self.implicit, true);
+        for item in items.iter_mut() {
+            self.visit_item_mut(item);
+        }
+        self.implicit = outer_implicit;
+        self.modules.pop();
+        // generated code refers to get_wye and capture unqualified:
+        items.insert(0, parse_quote!(#[allow(unused_imports)] use ::wye::prelude::*;));
     }
 
     fn visit_expr_macro_mut(&mut self, node: &mut ExprMacro) {
-        let syn::ExprMacro{mac, ..} = node;
-        let syn::Macro{path, tokens, ..} = mac;
-        if path.get_ident().is_some() {
-            let mut format_args: FormatArgs = parse2(tokens.clone()).unwrap();
-            for arg in format_args.0.iter_mut().skip(1) {
-                self.visit_expr_mut(arg);
-            }
-            *tokens = format_args.to_token_stream();
+        // other macros are left as they are; see compile:
+        let Some(mut args) = MacroArgs::parse(&node.mac) else { return };
+        let written = args.exprs_mut().into_iter().map(|arg| arg.to_token_stream()).collect::<Vec<_>>();
+        for arg in args.instrumented_mut() {
+            self.visit_place_mut(arg);
         }
+        // assert! and dbg! print the source of their arguments, which they
+        // are told as written rather than left to stringify as instrumented:
+        let name = node.mac.path.get_ident().map(Ident::to_string);
+        node.mac.tokens = match (name.as_deref(), &args) {
+            (Some("dbg"), MacroArgs::List{args, ..}) => {
+                let path = &node.mac.path;
+                node.mac.path = parse_quote_spanned!(path.span()=> ::wye::__dbg);
+                let args = args.iter();
+                quote!(#(stringify!(#written) => #args),*)
+            },
+            (Some("assert" | "debug_assert"), MacroArgs::List{args, ..}) if args.len() == 1 => {
+                let cond = &args[0];
+                let written = &written[0];
+                quote!(#cond, "{}", concat!("assertion failed: ", stringify!(#written)))
+            },
+            _ => args.to_token_stream(),
+        };
     }
 
     fn visit_expr_call_mut(&mut self, node: &mut ExprCall) {
@@ -558,20 +2004,188 @@ impl<'ast> VisitMut for Parts<'ast> {
         }
     }
 
+    fn visit_expr_reference_mut(&mut self, node: &mut ExprReference) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_place_mut(&mut node.expr);
+    }
+
+    fn visit_expr_assign_mut(&mut self, node: &mut ExprAssign) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_place_mut(&mut node.left);
+        self.visit_expr_mut(&mut node.right);
+    }
+
+    fn visit_expr_assign_op_mut(&mut self, node: &mut ExprAssignOp) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_place_mut(&mut node.left);
+        self.visit_expr_mut(&mut node.right);
+    }
+
+    fn visit_expr_binary_mut(&mut self, node: &mut ExprBinary) {
+        // comparisons borrow their operands:
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        if is_comparison(&node.op) {
+            self.visit_place_mut(&mut node.left);
+            self.visit_place_mut(&mut node.right);
+        } else {
+            self.visit_operand_mut(&mut node.left);
+            self.visit_operand_mut(&mut node.right);
+        }
+    }
+
+    fn visit_expr_unary_mut(&mut self, node: &mut ExprUnary) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_operand_mut(&mut node.expr);
+    }
+
+    fn visit_expr_paren_mut(&mut self, node: &mut ExprParen) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_operand_mut(&mut node.expr);
+    }
+
+    fn visit_expr_cast_mut(&mut self, node: &mut ExprCast) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_operand_mut(&mut node.expr);
+        self.visit_type_mut(&mut node.ty);
+    }
+
+    fn visit_expr_field_mut(&mut self, node: &mut ExprField) {
+        // like receivers, bases that are places are left in place:
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_place_mut(&mut node.base);
+    }
+
+    fn visit_expr_index_mut(&mut self, node: &mut ExprIndex) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_place_mut(&mut node.expr);
+        self.visit_operand_mut(&mut node.index);
+    }
+
+    fn visit_expr_tuple_mut(&mut self, node: &mut ExprTuple) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        for el in Punctuated::pairs_mut(&mut node.elems) {
+            let (it, _p) = el.into_tuple();
+            self.visit_operand_mut(it);
+        }
+    }
+
+    fn visit_expr_array_mut(&mut self, node: &mut ExprArray) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        for el in Punctuated::pairs_mut(&mut node.elems) {
+            let (it, _p) = el.into_tuple();
+            self.visit_operand_mut(it);
+        }
+    }
+
+    fn visit_expr_struct_mut(&mut self, node: &mut ExprStruct) {
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        self.visit_path_mut(&mut node.path);
+        for el in Punctuated::pairs_mut(&mut node.fields) {
+            let (it, _p) = el.into_tuple();
+            for attr in &mut it.attrs {
+                self.visit_attribute_mut(attr);
+            }
+            // shorthand fields are bare variables, which get no node
+            if it.colon_token.is_some() {
+                self.visit_operand_mut(&mut it.expr);
+            }
+        }
+        if let Some(it) = &mut node.rest {
+            self.visit_operand_mut(it);
+        }
+    }
+
+    fn visit_expr_method_call_mut(&mut self, node: &mut ExprMethodCall) {
+        // See syn::visit_mut::visit_expr_method_call_mut(self, node);
+        // however, the receiver is an argument, which Parts::receiver
+        // records instead if it is a place, and the method is not visited
+        for it in &mut node.attrs {
+            self.visit_attribute_mut(it);
+        }
+        if !is_pure_place(&node.receiver) {
+            self.visit_expr_call_arg_mut(&mut node.receiver);
+        }
+        for el in Punctuated::pairs_mut(&mut node.args) {
+            let (it, _p) = el.into_tuple();
+            self.visit_expr_call_arg_mut(it);
+        }
+    }
+
+    fn visit_block_mut(&mut self, node: &mut Block) {
+        let stmts = std::mem::take(&mut node.stmts);
+        let len = stmts.len();
+        for (ix, mut stmt) in stmts.into_iter().enumerate() {
+            let destructured = match &stmt {
+                Stmt::Local(local) if local_ident(&local.pat).is_none() => Some(local.clone()),
+                _ => None,
+            };
+            self.visit_stmt_mut(&mut stmt);
+            // block-like expressions, like ifs, need no semicolon before
+            // the next statement, but what they are instrumented into does:
+            if let (Stmt::Expr(expr), true) = (&stmt, ix + 1 < len) {
+                stmt = Stmt::Semi(expr.clone(), Default::default());
+            }
+            node.stmts.push(stmt);
+            if let Some(record) = destructured.and_then(|local| self.destructure(&local)) {
+                node.stmts.push(record);
+            }
+        }
+    }
+
     fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
         match stmt {
             Stmt::Local(local) => {
-                if let Local{attrs, let_token, pat: pat@syn::Pat::Ident(ident), init: Some((eq_token, expr)), semi_token} = &local {
+                if take_skip(&mut local.attrs) {
+                    if let (syn::Pat::Ident(pat_ident), Some((_, expr))) = (local.pat.clone(), local.init.as_mut()) {
+                        let ident = &pat_ident.ident;
+                        let ident_frame_var = format_ident!("__wye_frame_{}", ident);
+                        let ident_str = ident.to_string();
+                        let place = Bytespan::new(self.source_hash, ident.span()).site_id();
+                        let opaque = self.opaque(place, ident.span(), &ident_str, &ident_str, expr);
+                        **expr = parse_quote!((get_wye().frame().0, {
+                            let __wye_ret = #opaque;
+                            get_wye().push_frame(); get_wye().pop_frame();
+                            __wye_ret
+                        }));
+                        local.pat = parse_quote!((#ident_frame_var, #pat_ident));
+                    }
+                } else if let (Some((pat, ident, ty)), Local{attrs, let_token, init: Some((eq_token, expr)), semi_token, ..}) = (local_ident(&local.pat), &local) {
+                    let pat = syn::Pat::Ident(pat.clone());
                     let expr = Box::new(*expr.clone());
                     let mut fake_expr = Expr::Let(ExprLet{
                         attrs: attrs.clone(),
-                        let_token: let_token.clone(),
+                        let_token: *let_token,
                         pat: pat.clone(),
-                        eq_token: eq_token.clone(),
+                        eq_token: *eq_token,
                         expr,
                     });
-                    let ident_frame_var = format_ident!("__wye_frame_{}", ident.ident);
-                    self.compile(Some(hash(Bytespan::new(self.source_hash, ident.ident.span()))), &mut fake_expr);
+                    let ident_frame_var = format_ident!("__wye_frame_{}", ident);
+                    self.ascription = ty.map(|ty| Box::new(ty.clone()));
+                    self.compile(Some(Bytespan::new(self.source_hash, ident.span()).site_id()), &mut fake_expr);
                     let mut pat_elems: Punctuated<syn::Pat, Comma> = Default::default();
                     let pat_arg_frame = syn::Pat::Ident(syn::PatIdent{
                         attrs: vec![],
@@ -582,17 +2196,17 @@ impl<'ast> VisitMut for Parts<'ast> {
                     });
                     pat_elems.push_value(pat_arg_frame);
                     pat_elems.push_punct(syn::token::Comma::default());
-                    pat_elems.push_value(pat.clone());
+                    pat_elems.push_value(pat);
                     *stmt = Stmt::Local(Local{
                         attrs: attrs.clone(),
-                        let_token: let_token.clone(),
+                        let_token: *let_token,
                         pat: syn::Pat::Tuple(syn::PatTuple{
                             attrs: vec![],
                             paren_token: syn::token::Paren::default(),
                             elems: pat_elems,
                         }),
-                        init: Some((eq_token.clone(), Box::new(fake_expr))),
-                        semi_token: semi_token.clone(),
+                        init: Some((*eq_token, Box::new(fake_expr))),
+                        semi_token: *semi_token,
                     });
                 } else {
                     syn::visit_mut::visit_stmt_mut(self, stmt);
@@ -609,6 +2223,126 @@ impl<'ast> VisitMut for Parts<'ast> {
     }
 }
 
+/// Returns whether `attr` is `#[wye(skip)]` or `#[wye::ignore]`.
+fn is_skip(attr: &Attribute) -> bool {
+    let segments = attr.path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>();
+    match &segments[..] {
+        [wye] if wye == "wye" => attr.parse_args::<Ident>().is_ok_and(|arg| arg == "skip"),
+        [wye, ignore] => wye == "wye" && ignore == "ignore",
+        _ => false,
+    }
+}
+
+/// Removes any `#[wye(skip)]` or `#[wye::ignore]` attributes from `attrs`,
+/// returning whether there were any.
+fn take_skip(attrs: &mut Vec<Attribute>) -> bool {
+    let len = attrs.len();
+    attrs.retain(|attr| !is_skip(attr));
+    attrs.len() != len
+}
+
+/// Returns whether `attr` invokes [wye] itself, as `#[wye]`, `#[wye(...)]`,
+/// or `#[wye::wye]`, other than as `#[wye(skip)]`.
+fn is_wye(attr: &Attribute) -> bool {
+    let segments = attr.path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>();
+    let is_wye = matches!(&segments[..], [wye] | [_, wye] if wye == "wye");
+    is_wye && !is_skip(attr)
+}
+
+/// Returns whether an item nested in the annotated item should be left
+/// alone: because it is marked to be skipped, after removing the marks, or
+/// because it has a [wye] attribute of its own that will instrument it.
+fn leave_item(attrs: &mut Vec<Attribute>) -> bool {
+    take_skip(attrs) || attrs.iter().any(is_wye)
+}
+
+/// Returns the variables bound by `pat`, like `a` and `b` in
+/// `Multiple{a, b: (_, b), ..}`, in order.
+fn binders(pat: &syn::Pat) -> Vec<Ident> {
+    struct Binders(Vec<Ident>);
+
+    impl<'ast> Visit<'ast> for Binders {
+        fn visit_pat_ident(&mut self, node: &'ast PatIdent) {
+            if is_binder(node) && !self.0.contains(&node.ident) {
+                self.0.push(node.ident.clone());
+            }
+            syn::visit::visit_pat_ident(self, node);
+        }
+    }
+
+    let mut binders = Binders(vec![]);
+    binders.visit_pat(pat);
+    binders.0
+}
+
+/// Returns whether `pat_ident` binds a variable rather than naming a
+/// constant or a unit variant, like `None`, which, as in rustc's naming
+/// lints, are told apart by case.
+fn is_binder(pat_ident: &PatIdent) -> bool {
+    let is_upper = pat_ident.ident.to_string().starts_with(char::is_uppercase);
+    !is_upper || pat_ident.by_ref.is_some() || pat_ident.mutability.is_some() || pat_ident.subpat.is_some()
+}
+
+/// Splits the pattern of a let-binding of one variable, like `x`,
+/// `mut x`, or `x: u64`, into its identifier pattern, its identifier, and
+/// its ascribed type, if any.
+fn local_ident(pat: &syn::Pat) -> Option<(&PatIdent, &Ident, Option<&syn::Type>)> {
+    match pat {
+        syn::Pat::Ident(pat_ident) => Some((pat_ident, &pat_ident.ident, None)),
+        syn::Pat::Type(syn::PatType{pat, ty, ..}) => match pat.as_ref() {
+            syn::Pat::Ident(pat_ident) => Some((pat_ident, &pat_ident.ident, Some(ty.as_ref()))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn expr_attrs_mut(expr: &mut Expr) -> Option<&mut Vec<Attribute>> {
+    let attrs = match expr {
+        Expr::Array(expr) => &mut expr.attrs,
+        Expr::Assign(expr) => &mut expr.attrs,
+        Expr::AssignOp(expr) => &mut expr.attrs,
+        Expr::Async(expr) => &mut expr.attrs,
+        Expr::Await(expr) => &mut expr.attrs,
+        Expr::Binary(expr) => &mut expr.attrs,
+        Expr::Block(expr) => &mut expr.attrs,
+        Expr::Box(expr) => &mut expr.attrs,
+        Expr::Break(expr) => &mut expr.attrs,
+        Expr::Call(expr) => &mut expr.attrs,
+        Expr::Cast(expr) => &mut expr.attrs,
+        Expr::Closure(expr) => &mut expr.attrs,
+        Expr::Continue(expr) => &mut expr.attrs,
+        Expr::Field(expr) => &mut expr.attrs,
+        Expr::ForLoop(expr) => &mut expr.attrs,
+        Expr::Group(expr) => &mut expr.attrs,
+        Expr::If(expr) => &mut expr.attrs,
+        Expr::Index(expr) => &mut expr.attrs,
+        Expr::Let(expr) => &mut expr.attrs,
+        Expr::Lit(expr) => &mut expr.attrs,
+        Expr::Loop(expr) => &mut expr.attrs,
+        Expr::Macro(expr) => &mut expr.attrs,
+        Expr::Match(expr) => &mut expr.attrs,
+        Expr::MethodCall(expr) => &mut expr.attrs,
+        Expr::Paren(expr) => &mut expr.attrs,
+        Expr::Path(expr) => &mut expr.attrs,
+        Expr::Range(expr) => &mut expr.attrs,
+        Expr::Reference(expr) => &mut expr.attrs,
+        Expr::Repeat(expr) => &mut expr.attrs,
+        Expr::Return(expr) => &mut expr.attrs,
+        Expr::Struct(expr) => &mut expr.attrs,
+        Expr::Try(expr) => &mut expr.attrs,
+        Expr::TryBlock(expr) => &mut expr.attrs,
+        Expr::Tuple(expr) => &mut expr.attrs,
+        Expr::Type(expr) => &mut expr.attrs,
+        Expr::Unary(expr) => &mut expr.attrs,
+        Expr::Unsafe(expr) => &mut expr.attrs,
+        Expr::While(expr) => &mut expr.attrs,
+        Expr::Yield(expr) => &mut expr.attrs,
+        _ => return None,
+    };
+    Some(attrs)
+}
+
 fn as_ident(expr: &Expr) -> Option<&Ident> {
     if let Expr::Path(expr_path) = expr {
         return expr_path.path.get_ident()
@@ -616,6 +2350,200 @@ fn as_ident(expr: &Expr) -> Option<&Ident> {
     None
 }
 
+/// Names the syntactic kind of `expr` for the site manifest.
+fn expr_kind(expr: &Expr) -> String {
+    let kind = match expr {
+        Expr::Array(_) => "Array",
+        Expr::Assign(_) => "Assign",
+        Expr::AssignOp(_) => "AssignOp",
+        Expr::Async(_) => "Async",
+        Expr::Await(_) => "Await",
+        Expr::Binary(binary) => return format!("Expr::Binary({})", binary.op.to_token_stream()),
+        Expr::Block(_) => "Block",
+        Expr::Box(_) => "Box",
+        Expr::Break(_) => "Break",
+        Expr::Call(_) => "Call",
+        Expr::Cast(_) => "Cast",
+        Expr::Closure(_) => "Closure",
+        Expr::Continue(_) => "Continue",
+        Expr::Field(_) => "Field",
+        Expr::ForLoop(_) => "ForLoop",
+        Expr::Group(_) => "Group",
+        Expr::If(_) => "If",
+        Expr::Index(_) => "Index",
+        Expr::Let(_) => "Let",
+        Expr::Lit(_) => "Lit",
+        Expr::Loop(_) => "Loop",
+        Expr::Macro(_) => "Macro",
+        Expr::Match(_) => "Match",
+        Expr::MethodCall(_) => "MethodCall",
+        Expr::Paren(_) => "Paren",
+        Expr::Path(_) => "Path",
+        Expr::Range(_) => "Range",
+        Expr::Reference(_) => "Reference",
+        Expr::Repeat(_) => "Repeat",
+        Expr::Return(_) => "Return",
+        Expr::Struct(_) => "Struct",
+        Expr::Try(_) => "Try",
+        Expr::TryBlock(_) => "TryBlock",
+        Expr::Tuple(_) => "Tuple",
+        Expr::Type(_) => "Type",
+        Expr::Unary(_) => "Unary",
+        Expr::Unsafe(_) => "Unsafe",
+        Expr::While(_) => "While",
+        Expr::Yield(_) => "Yield",
+        _ => "Verbatim",
+    };
+    format!("Expr::{kind}")
+}
+
+/// Generates an expression that formats `value`, a reference, with the
+/// best formatting its type supports and, if possible, in structured form;
+/// see `wye::capture`.
+fn capture(value: Expr) -> Expr {
+    parse_quote!({
+        use capture::*;
+        let __wye_value = #value;
+        capture::value(
+            (&&&&&capture::Capture(__wye_value)).capture(),
+            (&&capture::Capture(__wye_value)).structured(),
+        )
+    })
+}
+
+/// Returns whether `expr` is a place, like `x`, `self.items`, or `v[i]`,
+/// that can be evaluated again without side effects.
+fn is_pure_place(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(path) => path.qself.is_none(),
+        Expr::Field(field) => is_pure_place(&field.base),
+        Expr::Paren(paren) => is_pure_place(&paren.expr),
+        Expr::Unary(ExprUnary{op: UnOp::Deref(_), expr, ..}) => is_pure_place(expr),
+        Expr::Index(index) => is_pure_place(&index.expr) && is_pure_index(&index.index),
+        _ => false,
+    }
+}
+
+/// Returns the parameters of `sig` that are `&mut` references, including a
+/// `&mut self` receiver, with their positions, if they are variables.
+fn mut_params(sig: &Signature) -> Vec<(usize, Ident)> {
+    sig.inputs.iter().enumerate().filter_map(|(index, input)| match input {
+        syn::FnArg::Receiver(Receiver{reference: Some(_), mutability: Some(_), self_token, ..}) => {
+            Some((index, Ident::new("self", self_token.span)))
+        },
+        syn::FnArg::Typed(syn::PatType{pat, ty, ..}) => match (pat.as_ref(), ty.as_ref()) {
+            (syn::Pat::Ident(pat_ident), syn::Type::Reference(syn::TypeReference{mutability: Some(_), ..})) => {
+                Some((index, pat_ident.ident.clone()))
+            },
+            _ => None,
+        },
+        _ => None,
+    }).collect()
+}
+
+/// Returns whether `output`, a function's return type, may borrow from its
+/// arguments, like `&mut T`, `Iter<'_, T>`, or `impl Iterator + '_`.
+fn may_borrow(output: &syn::ReturnType) -> bool {
+    let syn::ReturnType::Type(_, ty) = output else { return false };
+    let mut borrows = Borrows(false);
+    borrows.visit_type(ty);
+    borrows.0
+}
+
+/// Finds, in a type, the references, non-`'static` lifetimes and `impl`
+/// traits through which it may borrow; see [may_borrow].
+struct Borrows(bool);
+
+impl<'ast> Visit<'ast> for Borrows {
+    fn visit_type_reference(&mut self, _: &'ast syn::TypeReference) {
+        self.0 = true;
+    }
+
+    fn visit_type_impl_trait(&mut self, _: &'ast syn::TypeImplTrait) {
+        self.0 = true;
+    }
+
+    fn visit_lifetime(&mut self, lifetime: &'ast syn::Lifetime) {
+        self.0 |= lifetime.ident != "static";
+    }
+}
+
+/// Returns the variable at the root of `place`, a place that
+/// [is_pure_place], like `p` in `p.items[0]` or `r` in `*r`, if any.
+fn place_root(place: &Expr) -> Option<&Ident> {
+    match place {
+        Expr::Path(_) => as_ident(place),
+        Expr::Field(field) => place_root(&field.base),
+        Expr::Paren(paren) => place_root(&paren.expr),
+        Expr::Unary(ExprUnary{expr, ..}) => place_root(expr),
+        Expr::Index(index) => place_root(&index.expr),
+        _ => None,
+    }
+}
+
+/// Returns whether `index` is a literal, a place, or a range of them, like
+/// the `..` in `aa[..]` or the `i..` in `v[i..]`.
+fn is_pure_index(index: &Expr) -> bool {
+    match index {
+        Expr::Lit(_) => true,
+        Expr::Range(range) => [&range.from, &range.to].into_iter().flatten().all(|bound| is_pure_index(bound)),
+        _ => is_pure_place(index),
+    }
+}
+
+/// Returns whether `expr`, a call's argument, is a place or a borrow of
+/// one, like `&aa[..]`, `&self.items`, or `*r`, whose value comes only from
+/// the variables it uses.
+fn is_place_arg(expr: &Expr) -> bool {
+    match expr {
+        Expr::Reference(reference) => is_pure_place(&reference.expr),
+        Expr::Paren(paren) => is_place_arg(&paren.expr),
+        _ => is_pure_place(expr),
+    }
+}
+
+/// Returns whether `expr` never produces a value, like `return x`,
+/// `panic!(..)`, or a block ending in one.
+fn diverges(expr: &Expr) -> bool {
+    match expr {
+        Expr::Macro(ExprMacro{mac, ..}) => {
+            ["panic", "unreachable", "todo", "unimplemented"].iter().any(|name| mac.path.is_ident(name))
+        },
+        Expr::Block(ExprBlock{block, ..}) => match block.stmts.last() {
+            Some(Stmt::Expr(tail)) | Some(Stmt::Semi(tail, _)) => diverges(tail),
+            _ => false,
+        },
+        _ => is_diverging(expr),
+    }
+}
+
+/// Returns `bindings` without the uses inside `operands`, which got nodes of
+/// their own; see [Parts::visit_operand_mut].
+fn outside(bindings: Vec<Binding>, operands: &[Range<proc_macro::LineColumn>]) -> Vec<Binding> {
+    bindings.into_iter()
+        .filter(|(var_range, ..)| !operands.iter().any(|operand| operand.start <= var_range.start && var_range.end <= operand.end))
+        .collect()
+}
+
+fn is_comparison(op: &BinOp) -> bool {
+    matches!(op, BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_))
+}
+
+/// Returns whether `expr` is a `return`, `break` or `continue`, whose type
+/// falls back to `()`, which does not implement `WyeValue`, so that it
+/// cannot be captured like other values.
+fn is_diverging(expr: &Expr) -> bool {
+    matches!(expr, Expr::Return(_) | Expr::Break(_) | Expr::Continue(_))
+}
+
+/// Returns whether `expr` is left without a node of its own: diverging
+/// expressions have no value to record, and closures, whose bodies are
+/// still instrumented, must stay where they are passed for their parameter
+/// types to be inferred from the callee's bounds.
+fn is_unrecorded(expr: &Expr) -> bool {
+    is_diverging(expr) || matches!(expr, Expr::Closure(_))
+}
+
 fn as_binop(expr: &Expr) -> Option<&BinOp> {
     if let Expr::Binary(expr_binary) = expr {
         return Some(&expr_binary.op)
@@ -623,32 +2551,232 @@ fn as_binop(expr: &Expr) -> Option<&BinOp> {
     None
 }
 
-fn hash<T: Hash>(t: T) -> u64 {
-    let mut h = DefaultHasher::new();
-    t.hash(&mut h);
-    h.finish()
+/// Returns the path of the file containing the current macro invocation,
+/// relative to the crate being compiled when possible and `/`-separated,
+/// so that it does not depend on where the crate happens to be checked out
+/// or on the platform it is built on.
+fn source_file() -> String {
+    let path = proc_macro::Span::call_site().source_file().path();
+    // rustc reports paths relative to its working directory, which cargo
+    // sets to the workspace root rather than to the crate's:
+    let path = match std::env::current_dir() {
+        Ok(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    };
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = path.strip_prefix(&manifest_dir).unwrap_or(&path);
+    path.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/")
+}
+
+/// Returns the FNV-1a state after hashing the current crate name and
+/// [source_file]; see "Site IDs" above.
+fn source_hash() -> u64 {
+    let krate = std::env::var("CARGO_CRATE_NAME").unwrap_or_default();
+    let Fnv(source_hash) = Fnv::new()
+        .write(krate.as_bytes())
+        .write(&[0])
+        .write(source_file().as_bytes())
+        .write(&[0]);
+    source_hash
+}
+
+/// Static metadata describing one instrumented site; see "Site Manifest"
+/// above.
+#[derive(Clone, Debug)]
+struct Site {
+    id: u64,
+    function: Option<String>,
+    bytespan: Bytespan,
+    text: String,
+    kind: String,
+}
+
+/// Writes the sites instrumented by the current macro invocation to the
+/// site manifest, if there is somewhere to put it.
+///
+/// Failing to write the manifest should not fail the build, so errors
+/// are ignored.
+fn write_manifest(source_hash: u64, file: &str, sites: &[Site]) {
+    let dir = std::env::var_os("WYE_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("OUT_DIR").map(|out_dir| PathBuf::from(out_dir).join("wye-sites")));
+    let Some(dir) = dir else { return };
+    let krate = std::env::var("CARGO_CRATE_NAME").unwrap_or_default();
+    let invocation = Bytespan::new(source_hash, Span::call_site()).site_id();
+
+    let mut out = String::new();
+    for Site{id, function, bytespan: Bytespan{start, end, ..}, text, kind} in sites {
+        let (start_line, start_column) = start.position();
+        let (end_line, end_column) = end.position();
+        let function = function.as_deref().map(json_string).unwrap_or_else(|| "null".into());
+        writeln!(out,
+            r#"{{"id":{id},"crate":{},"function":{function},"file":{},"start":[{},{}],"end":[{},{}],"text":{},"kind":{}}}"#,
+            json_string(&krate),
+            json_string(file),
+            start_line, start_column,
+            end_line, end_column,
+            json_string(text),
+            json_string(kind),
+        ).unwrap();
+    }
+    let path = dir.join(format!("{krate}-{invocation:016x}.jsonl"));
+    let _ = std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(path, out));
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 64-bit FNV-1a, which, unlike [std::collections::hash_map::DefaultHasher],
+/// is specified independently of any particular Rust release.
+#[derive(Copy, Clone, Debug)]
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(self, bytes: &[u8]) -> Self {
+        let Fnv(mut h) = self;
+        for byte in bytes {
+            h ^= *byte as u64;
+            h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        Fnv(h)
+    }
 }
 
+/// The arguments of an invocation of one of the std macros that wye
+/// recognizes, parsed so that those that are expressions can be
+/// instrumented; other macros are recorded as opaque nodes.
 #[derive(Debug)]
-struct FormatArgs(Punctuated<Expr, Comma>);
+enum MacroArgs {
+    /// Expressions, like those of `dbg!(x)` or `assert_eq!(a, b, "{}", c)`,
+    /// of which the one at `format`, if any, is a format string, which is
+    /// left as it is.
+    List{args: Punctuated<Expr, Comma>, format: Option<usize>},
+    /// The elements of `vec![a, b]`.
+    Elements{args: Punctuated<Expr, Comma>},
+    /// The element and length of `vec![elem; len]`.
+    Repeat{elem: Box<Expr>, semi_token: syn::Token![;], len: Box<Expr>},
+    /// The scrutinee of `matches!(expr, pattern)`, whose pattern, and guard,
+    /// if any, are left as they are.
+    Matches{expr: Box<Expr>, comma: Comma, pattern: TokenStream},
+}
 
-impl syn::parse::Parse for FormatArgs {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let mut format_args: Punctuated<Expr, Comma> = Punctuated::new();
-        loop {
-            format_args.push_value(input.parse()?);
-            if input.is_empty() {
-                break;
-            }
-            format_args.push_punct(input.parse()?);
+impl MacroArgs {
+    /// Parses the arguments of `mac`, if it is a macro wye recognizes and
+    /// they parse.
+    fn parse(mac: &syn::Macro) -> Option<Self> {
+        let name = mac.path.get_ident()?.to_string();
+        let format = match name.as_str() {
+            "format" | "format_args" | "print" | "println" | "eprint" | "eprintln"
+                | "panic" | "unreachable" | "todo" | "unimplemented" => Some(0),
+            "write" | "writeln" | "assert" | "debug_assert" => Some(1),
+            "assert_eq" | "assert_ne" | "debug_assert_eq" | "debug_assert_ne" => Some(2),
+            "dbg" => None,
+            "vec" => return mac.parse_body_with(Self::parse_vec).ok(),
+            "matches" => return mac.parse_body_with(Self::parse_matches).ok(),
+            _ => return None,
+        };
+        let args = mac.parse_body_with(Punctuated::parse_terminated).ok()?;
+        Some(Self::List{args, format})
+    }
+
+    fn parse_vec(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(Self::Elements{args: Punctuated::new()});
+        }
+        let elem = input.parse()?;
+        if input.peek(syn::Token![;]) {
+            return Ok(Self::Repeat{elem: Box::new(elem), semi_token: input.parse()?, len: input.parse()?});
+        }
+        let mut args = Punctuated::new();
+        args.push_value(elem);
+        if !input.is_empty() {
+            args.push_punct(input.parse()?);
+            args.extend(Punctuated::<Expr, Comma>::parse_terminated(input)?.into_pairs());
+        }
+        Ok(Self::Elements{args})
+    }
+
+    fn parse_matches(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self::Matches{expr: input.parse()?, comma: input.parse()?, pattern: input.parse()?})
+    }
+
+    /// Returns the arguments that are expressions: those other than format
+    /// strings, and the values of named format arguments, like `x` in
+    /// `format!("{a}", a = x)`.
+    fn exprs_mut(&mut self) -> Vec<&mut Expr> {
+        match self {
+            Self::Elements{args} => args.iter_mut().collect(),
+            Self::List{args, format} => args.iter_mut().enumerate()
+                .filter(|(index, _)| Some(*index) != *format)
+                .map(|(_, arg)| match arg {
+                    Expr::Assign(ExprAssign{right, ..}) => right.as_mut(),
+                    arg => arg,
+                })
+                .collect(),
+            Self::Repeat{elem, len, ..} => vec![elem.as_mut(), len.as_mut()],
+            Self::Matches{expr, ..} => vec![expr.as_mut()],
+        }
+    }
+
+    /// Returns the format string, if there is one and it is a literal, with
+    /// the names of the named arguments that follow it.
+    fn format_string(&self) -> Option<(syn::LitStr, Vec<String>)> {
+        let Self::List{args, format: Some(format)} = self else { return None };
+        let Some(Expr::Lit(syn::ExprLit{lit: syn::Lit::Str(format_string), ..})) = args.iter().nth(*format) else { return None };
+        let named = args.iter().skip(format + 1)
+            .filter_map(|arg| match arg {
+                Expr::Assign(ExprAssign{left, ..}) => as_ident(left).map(Ident::to_string),
+                _ => None,
+            })
+            .collect();
+        Some((format_string.clone(), named))
+    }
+
+    /// Returns the arguments to instrument: those of [MacroArgs::exprs_mut]
+    /// other than the elements of `vec!`s, whose types are often inferred
+    /// from how the vec is used, too late for their values to be recorded;
+    /// their variables still get edges to the vec's node.
+    fn instrumented_mut(&mut self) -> Vec<&mut Expr> {
+        match self {
+            Self::Elements{..} | Self::Repeat{..} => vec![],
+            _ => self.exprs_mut(),
         }
-        Ok(FormatArgs(format_args))
     }
 }
 
-impl quote::ToTokens for FormatArgs {
+impl quote::ToTokens for MacroArgs {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        tokens.append_separated(&self.0, Comma::default());
+        match self {
+            Self::List{args, ..} | Self::Elements{args} => args.to_tokens(tokens),
+            Self::Repeat{elem, semi_token, len} => {
+                elem.to_tokens(tokens);
+                semi_token.to_tokens(tokens);
+                len.to_tokens(tokens);
+            },
+            Self::Matches{expr, comma, pattern} => {
+                expr.to_tokens(tokens);
+                comma.to_tokens(tokens);
+                pattern.to_tokens(tokens);
+            },
+        }
     }
 }
 
@@ -722,12 +2850,66 @@ impl quote::ToTokens for WyeArgs {
 type WyeArgMap = HashMap<Ident, Expr>;
 
 impl WyeArgs {
-    fn process(&self) -> WyeArgMap {
+    /// Splits the arguments into custom formats and [Filters], given by
+    /// the `include` and `exclude` arguments.
+    fn process(&self) -> syn::Result<(WyeArgMap, Filters)> {
         let mut args = HashMap::new();
+        let mut filters = Filters::default();
         for arg in self.0.iter() {
-            args.insert(arg.ident.clone(), arg.expr.clone());
+            if arg.ident == "include" {
+                filters.include.extend(patterns(&arg.expr)?);
+            } else if arg.ident == "exclude" {
+                filters.exclude.extend(patterns(&arg.expr)?);
+            } else {
+                args.insert(arg.ident.clone(), arg.expr.clone());
+            }
         }
-        args
+        Ok((args, filters))
+    }
+}
+
+/// Glob patterns, in which `*` matches anything, that select the functions
+/// and methods instrumented by name, like `net::*` or `Counter::incr`; see
+/// [Parts::visit_fn_mut].
+#[derive(Clone, Debug, Default)]
+struct Filters {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl Filters {
+    /// Returns whether `name` matches some included pattern, if there are
+    /// any, and no excluded pattern.
+    fn admits(&self, name: &str) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|pattern| glob(pattern, name));
+        included && !self.exclude.iter().any(|pattern| glob(pattern, name))
+    }
+}
+
+/// Returns the patterns given by `expr`, either a string literal or an
+/// array of them.
+fn patterns(expr: &Expr) -> syn::Result<Vec<String>> {
+    let exprs = match expr {
+        Expr::Array(array) => array.elems.iter().collect(),
+        expr => vec![expr],
+    };
+    exprs.into_iter().map(|expr| match expr {
+        Expr::Lit(syn::ExprLit{lit: syn::Lit::Str(lit), ..}) => Ok(lit.value()),
+        expr => Err(syn::Error::new(expr.span(), "expected a string literal pattern")),
+    }).collect()
+}
+
+/// Returns whether `name` matches `pattern`, in which `*` matches any
+/// sequence of characters.
+fn glob(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, pattern)) => name.strip_prefix(prefix).is_some_and(|name| {
+            name.char_indices()
+                .map(|(ix, _)| ix)
+                .chain([name.len()])
+                .any(|ix| glob(pattern, &name[ix..]))
+        }),
     }
 }
 
@@ -771,13 +2953,20 @@ pub fn wye(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> pro
     let args = if !args.is_empty() {
         parse_macro_input!(args as WyeArgs).process()
     } else {
-        WyeArgMap::new()
+        Ok(Default::default())
+    };
+    let args = match args {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error().into(),
     };
-    let _ = args;
 
     let mut input = parse_macro_input!(input as Item);
+    if let Some(kind) = uninstrumented_kind(&input) {
+        let message = format!("wye cannot instrument {kind}; it applies to fns, impls, traits, and mods");
+        return syn::Error::new_spanned(&input, message).to_compile_error().into();
+    }
 
-    let source_hash = hash(proc_macro::Span::call_site().source_text().unwrap());
+    let source_hash = source_hash();
 
     let mut scopes = Scopes::new(source_hash);
     scopes.visit_item(&input);
@@ -785,21 +2974,139 @@ pub fn wye(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> pro
     let mut uses = Uses::new(source_hash);
     uses.visit_item(&input);
 
-    let mut parts = Parts::new(source_hash, &scopes, &uses);
+    let file = source_file();
+    let mut parts = Parts::new(source_hash, file.clone(), &scopes, &uses, args);
     parts.visit_item_mut(&mut input);
+    if let Err(error) = parts.check() {
+        return error.to_compile_error().into();
+    }
+    write_manifest(source_hash, &file, &parts.sites);
 
     let tokens = input.into_token_stream();
     tokens.into()
 }
 
+/// Returns the kind of `item`, like "structs", if [wye] has nothing in
+/// it to instrument.
+fn uninstrumented_kind(item: &Item) -> Option<&'static str> {
+    match item {
+        Item::Fn(_) | Item::Impl(_) | Item::Trait(_) | Item::Mod(_) => None,
+        Item::Const(_) => Some("consts"),
+        Item::Static(_) => Some("statics"),
+        Item::Struct(_) => Some("structs"),
+        Item::Enum(_) => Some("enums"),
+        Item::Union(_) => Some("unions"),
+        Item::Type(_) => Some("type aliases"),
+        Item::Use(_) => Some("`use` declarations"),
+        Item::ExternCrate(_) => Some("`extern crate` declarations"),
+        Item::ForeignMod(_) => Some("`extern` blocks"),
+        _ => Some("this item"),
+    }
+}
+
+/// Derives `wye::WyeValue`, labeling structs with their name and enums
+/// with their variant's path, and recording each field as a child except
+/// those marked `#[wye(skip)]`; fields marked `#[wye(redact)]` are recorded
+/// as `<redacted>`.
+#[proc_macro_derive(WyeValue, attributes(wye))]
+pub fn derive_wye_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match wye_value(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn wye_value(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let variants: Vec<(TokenStream, String, &Fields)> = match &input.data {
+        Data::Struct(data) => vec![(quote!(Self), name.to_string(), &data.fields)],
+        Data::Enum(data) => data.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            (quote!(Self::#ident), format!("{name}::{ident}"), &variant.fields)
+        }).collect(),
+        Data::Union(_) => return Err(syn::Error::new(name.span(), "WyeValue cannot be derived for unions")),
+    };
+
+    let mut labels = vec![];
+    let mut children = vec![];
+    for (path, label, fields) in variants {
+        labels.push(quote!(#path{..} => #label.to_string(),));
+        let mut pats = vec![];
+        let mut values = vec![];
+        for (ix, field) in fields.iter().enumerate() {
+            let member = match &field.ident {
+                Some(ident) => quote!(#ident),
+                None => syn::Index::from(ix).into_token_stream(),
+            };
+            let child = field.ident.as_ref().map_or_else(|| ix.to_string(), |ident| ident.to_string());
+            let binding = format_ident!("__wye_field_{}", ix);
+            match field_option(&field.attrs)?.as_deref() {
+                Some("skip") => continue,
+                Some("redact") => {
+                    values.push(quote!((#child.to_string(), ::wye::Value::from(::wye::policy::REDACTED))));
+                },
+                _ => {
+                    values.push(quote!((#child.to_string(), {
+                        use ::wye::capture::*;
+                        value((&&&&&Capture(#binding)).capture(), (&&Capture(#binding)).structured())
+                    })));
+                },
+            }
+            pats.push(quote!(#member: #binding));
+        }
+        children.push(quote!(#path{#(#pats,)* ..} => vec![#(#values),*],));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote!(
+        impl #impl_generics ::wye::WyeValue for #name #ty_generics #where_clause {
+            fn label(&self) -> String {
+                match self {
+                    #(#labels)*
+                }
+            }
+
+            fn children(&self) -> Vec<(String, ::wye::Value)> {
+                match self {
+                    #(#children)*
+                }
+            }
+        }
+    ))
+}
+
+/// Returns the option given by a field's `#[wye(skip)]` or `#[wye(redact)]`
+/// attribute, if any.
+fn field_option(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let mut option = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("wye")) {
+        let ident: Ident = attr.parse_args()?;
+        if ident != "skip" && ident != "redact" {
+            return Err(syn::Error::new(ident.span(), "expected `skip` or `redact`"));
+        }
+        option = Some(ident.to_string());
+    }
+    Ok(option)
+}
+
+/// Marks code to be left uninstrumented by [wye] and [wyre]; outside of
+/// them, it does nothing.
+#[proc_macro_attribute]
+pub fn ignore(_args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    input
+}
+
 #[proc_macro]
 pub fn wyre(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut input = parse_macro_input!(input as WyreExpr);
 
-    let args = input.args.as_ref().map(|args| args.1.process()).unwrap_or_else(WyeArgMap::new);
-    let _ = args;
+    let args = match input.args.as_ref().map(|args| args.1.process()).unwrap_or_else(|| Ok(Default::default())) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
-    let source_hash = hash(proc_macro::Span::call_site().source_text().unwrap());
+    let source_hash = source_hash();
     let span = input.span().unwrap();
 
     let mut scopes = Scopes::new(source_hash);
@@ -813,10 +3120,15 @@ pub fn wyre(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         uses.visit_stmt(stmt)
     }
 
-    let mut parts = Parts::new(source_hash, &scopes, &uses);
-    for stmt in &mut input.stmts.0 {
-        parts.visit_stmt_mut(stmt);
+    let file = source_file();
+    let mut parts = Parts::new(source_hash, file.clone(), &scopes, &uses, args);
+    let mut block = Block{brace_token: Default::default(), stmts: std::mem::take(&mut input.stmts.0)};
+    parts.visit_block_mut(&mut block);
+    input.stmts.0 = block.stmts;
+    if let Err(error) = parts.check() {
+        return error.to_compile_error().into();
     }
+    write_manifest(source_hash, &file, &parts.sites);
 
     input.stmts.0.insert(0, parse_quote!(__wye.push_frame();));
     input.stmts.0.insert(0, parse_quote!(let __wye = get_wye();));
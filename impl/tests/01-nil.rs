@@ -2,11 +2,14 @@
 use wye::*;
 
 #[wye]
+#[allow(dead_code)]
 fn add(a: u64, b: u64) -> u64 { a + b }
 
 #[wye(a: format!("{a:?}"), b: format!("b"))]
+#[allow(dead_code)]
 fn add1(a: u64, b: u64) -> u64 { a + b }
 
+#[allow(unused_variables)]
 pub fn main() {
     wyre!{};
     wyre!{()};
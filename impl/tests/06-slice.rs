@@ -0,0 +1,71 @@
+// Check that borrowed, indexed, and dereferenced arguments get nodes of
+// their own, with edges from the variables they use.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[wye]
+fn concat(a: &str, b: &str) -> String { format!("{} {}", a, b) }
+
+#[wye]
+fn first(xs: &[u64]) -> u64 { xs[0] }
+
+#[wye]
+fn add(a: u64, b: u64) -> u64 { a + b }
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "concat(&aa[..], &bb[..]) = \"a b\"" ]
+    1 [ label = "&aa[..] = \"a\"" ]
+    2 [ label = "&bb[..] = \"b\"" ]
+    3 [ label = "a = \"a\"" ]
+    4 [ label = "b = \"b\"" ]
+    5 [ label = "format!(\"{} {}\", a, b) = \"a b\"" ]
+    6 [ label = "v = [3, 4, 5]" ]
+    7 [ label = "r = 5" ]
+    8 [ label = "add(first(&v[1..]), *r) = 9" ]
+    9 [ label = "first(&v[1..]) = 4" ]
+    10 [ label = "&v[1..] = [4, 5]" ]
+    11 [ label = "xs = [4, 5]" ]
+    12 [ label = "4" ]
+    13 [ label = "0" ]
+    14 [ label = "*r = 5" ]
+    15 [ label = "a = 4" ]
+    16 [ label = "b = 5" ]
+    17 [ label = "+ = 9" ]
+    1 -> 3 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
+    4 -> 5 [ label = "" ]
+    5 -> 0 [ label = "" ]
+    6 -> 7 [ label = "" ]
+    6 -> 10 [ label = "" ]
+    10 -> 11 [ label = "" ]
+    13 -> 12 [ label = "" ]
+    11 -> 12 [ label = "" ]
+    12 -> 9 [ label = "" ]
+    6 -> 9 [ label = "" ]
+    7 -> 14 [ label = "" ]
+    9 -> 15 [ label = "" ]
+    14 -> 16 [ label = "" ]
+    15 -> 17 [ label = "" ]
+    16 -> 17 [ label = "" ]
+    17 -> 8 [ label = "" ]
+    6 -> 8 [ label = "" ]
+    7 -> 8 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    let aa = String::from("a");
+    let bb = String::from("b");
+    assert_eq!(wyre!{
+        concat(&aa[..], &bb[..])
+    }, "a b");
+    assert_eq!(wyre!{
+        let v = vec![3, 4, 5];
+        let r = &v[2];
+        add(first(&v[1..]), *r)
+    }, 9);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
@@ -1,6 +1,7 @@
 use std::ops::Deref;
 
-// Check that the simplest use of the #[wye] attribute proc-macro compiles.
+// Check that per-argument formatters let #[wye] and wyre! record values
+// that are only Debug or only Display.
 use wye::*;
 use pretty_assertions::{assert_eq};
 
@@ -41,24 +42,25 @@ fn concat(a: OnlyDebug, b: OnlyDisplay) -> String { format!("{:?} {}", &a, &b) }
 
 const EXPECTED_GRAPH: &str = r#"
 digraph {
-    0 [ label = "aa = a" ]
-    1 [ label = "bb = b" ]
-    2 [ label = "a = a" ]
-    3 [ label = "b = b" ]
-    4 [ label = "format! (\"{:?} {}\", & a, & b)" ]
-    5 [ label = "a b" ]
-    0 -> 2 [ label = "" ]
+    0 [ label = "concat(aa, bb) = \"a b\"" ]
+    1 [ label = "aa = a" ]
+    2 [ label = "bb = b" ]
+    3 [ label = "a = a" ]
+    4 [ label = "b = b" ]
+    5 [ label = "format!(\"{:?} {}\", &a, &b) = \"a b\"" ]
+    6 [ label = "a" ]
+    7 [ label = "b" ]
     1 -> 3 [ label = "" ]
     2 -> 4 [ label = "" ]
-    3 -> 4 [ label = "" ]
-    4 -> 5 [ label = "" ]
+    3 -> 6 [ label = "" ]
+    4 -> 7 [ label = "" ]
+    6 -> 5 [ label = "" ]
+    7 -> 5 [ label = "" ]
+    5 -> 0 [ label = "" ]
 }
 "#;
 
 pub fn main() {
-    // BUG: this test is insufficiently precise; it needs to validate that
-    // the reference / slice expressions passed to concat get handled via 
-    // push_var().
     let aa = OnlyDebug("a");
     let bb = OnlyDisplay("b");
     assert_eq!(wyre!{
@@ -0,0 +1,41 @@
+use std::fmt::Display;
+
+// Check that #[wye] supports `impl Trait` in argument position.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[wye]
+fn concat(a: impl AsRef<str> + Display, b: impl AsRef<str> + Display) -> String { format!("{} {}", &a, b.as_ref()) }
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "concat(aa, bb) = \"a b\"" ]
+    1 [ label = "aa = \"a\"" ]
+    2 [ label = "bb = \"b\"" ]
+    3 [ label = "a = a" ]
+    4 [ label = "b = b" ]
+    5 [ label = "format!(\"{} {}\", &a, b.as_ref()) = \"a b\"" ]
+    6 [ label = "a" ]
+    7 [ label = "b.as_ref() = \"b\"" ]
+    8 [ label = "b = b" ]
+    1 -> 3 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    3 -> 6 [ label = "" ]
+    4 -> 8 [ label = "" ]
+    4 -> 7 [ label = "" ]
+    8 -> 7 [ label = "" ]
+    6 -> 5 [ label = "" ]
+    7 -> 5 [ label = "" ]
+    5 -> 0 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    let aa = "a";
+    let bb = "b";
+    assert_eq!(wyre!{
+        concat(aa, bb)
+    }, "a b");
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
\ No newline at end of file
@@ -1,6 +1,6 @@
 use std::fmt::{Display};
 
-// Check that the simplest use of the #[wye] attribute proc-macro compiles.
+// Check that #[wye] supports where clauses.
 use wye::*;
 use pretty_assertions::{assert_eq};
 
@@ -17,17 +17,17 @@ fn concat<V>(a: V, b: V) -> String where V: Display { format!("{} {}", a, b) }
 
 const EXPECTED_GRAPH: &str = r#"
 digraph {
-    0 [ label = "aa = a" ]
-    1 [ label = "bb = b" ]
-    2 [ label = "a = a" ]
-    3 [ label = "b = b" ]
-    4 [ label = "format! (\"{} {}\", a, b)" ]
-    5 [ label = "a b" ]
-    0 -> 2 [ label = "" ]
+    0 [ label = "concat(aa, bb) = \"a b\"" ]
+    1 [ label = "aa = a" ]
+    2 [ label = "bb = b" ]
+    3 [ label = "a = a" ]
+    4 [ label = "b = b" ]
+    5 [ label = "format!(\"{} {}\", a, b) = \"a b\"" ]
     1 -> 3 [ label = "" ]
     2 -> 4 [ label = "" ]
-    3 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
     4 -> 5 [ label = "" ]
+    5 -> 0 [ label = "" ]
 }
 "#;
 
@@ -0,0 +1,118 @@
+use std::fmt::{Debug, Display};
+
+// Check that #[wye] supports several type parameters and const generics,
+// and that typed let-bindings still guide inference.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+struct Datum<V, E>(V, E) where V: Display, E: Display;
+
+impl<V, E> Display for Datum<V, E> where V: Display, E: Display {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)?;
+        write!(f, " ")?;
+        self.1.fmt(f)
+    }
+}
+
+#[wye]
+fn concat<V, E>(a: V, b: E) -> String where V: Display, E: Display { format!("{} {}", a, b) }
+
+#[wye]
+fn total<T: Copy + Debug + Into<u64>, const N: usize>(xs: [T; N]) -> u64 {
+    let first: u64 = xs[0].into();
+    let sum: u64 = xs.iter().fold(0, |acc, x| acc + Into::<u64>::into(*x));
+    first + sum + N as u64
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "concat(aa, bb) = \"a a b b\"" ]
+    1 [ label = "aa = a a" ]
+    2 [ label = "bb = b b" ]
+    3 [ label = "a = a a" ]
+    4 [ label = "b = b b" ]
+    5 [ label = "format!(\"{} {}\", a, b) = \"a a b b\"" ]
+    6 [ label = "total::<u8, 3>(xs) = 10" ]
+    7 [ label = "xs = [1, 2, 3]" ]
+    8 [ label = "xs = [1, 2, 3]" ]
+    9 [ label = "first = 1" ]
+    10 [ label = "xs[0].into() = 1" ]
+    11 [ label = "xs[0] = 1" ]
+    12 [ label = "sum = 6" ]
+    13 [ label = "xs.iter().fold(0, |acc, x| acc + Into::<u64>::into(*x)) = 6" ]
+    14 [ label = "xs.iter() = Iter([1, 2, 3])" ]
+    15 [ label = "xs = [1, 2, 3]" ]
+    16 [ label = "0" ]
+    17 [ label = "acc = 0" ]
+    18 [ label = "x = 1" ]
+    19 [ label = "+ = 1" ]
+    20 [ label = "Into::<u64>::into(*x) = 1" ]
+    21 [ label = "*x = 1" ]
+    22 [ label = "acc = 1" ]
+    23 [ label = "x = 2" ]
+    24 [ label = "+ = 3" ]
+    25 [ label = "Into::<u64>::into(*x) = 2" ]
+    26 [ label = "*x = 2" ]
+    27 [ label = "acc = 3" ]
+    28 [ label = "x = 3" ]
+    29 [ label = "+ = 6" ]
+    30 [ label = "Into::<u64>::into(*x) = 3" ]
+    31 [ label = "*x = 3" ]
+    32 [ label = "+ = 10" ]
+    33 [ label = "+ = 7" ]
+    34 [ label = "3" ]
+    1 -> 3 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
+    4 -> 5 [ label = "" ]
+    5 -> 0 [ label = "" ]
+    7 -> 8 [ label = "" ]
+    8 -> 11 [ label = "" ]
+    8 -> 10 [ label = "" ]
+    11 -> 10 [ label = "" ]
+    10 -> 9 [ label = "" ]
+    8 -> 15 [ label = "" ]
+    8 -> 14 [ label = "" ]
+    15 -> 14 [ label = "" ]
+    18 -> 21 [ label = "" ]
+    18 -> 20 [ label = "" ]
+    21 -> 20 [ label = "" ]
+    20 -> 19 [ label = "" ]
+    17 -> 19 [ label = "" ]
+    19 -> 13 [ label = "" ]
+    23 -> 26 [ label = "" ]
+    23 -> 25 [ label = "" ]
+    26 -> 25 [ label = "" ]
+    25 -> 24 [ label = "" ]
+    22 -> 24 [ label = "" ]
+    24 -> 13 [ label = "" ]
+    28 -> 31 [ label = "" ]
+    28 -> 30 [ label = "" ]
+    31 -> 30 [ label = "" ]
+    30 -> 29 [ label = "" ]
+    27 -> 29 [ label = "" ]
+    29 -> 13 [ label = "" ]
+    8 -> 13 [ label = "" ]
+    13 -> 12 [ label = "" ]
+    9 -> 33 [ label = "" ]
+    12 -> 33 [ label = "" ]
+    33 -> 32 [ label = "" ]
+    34 -> 32 [ label = "" ]
+    32 -> 6 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    let aa = Datum("a", "a");
+    let bb = Datum("b", "b");
+    assert_eq!(
+        wyre!{
+            concat(aa, bb)
+        }
+        , "a a b b");
+    let xs = [1u8, 2, 3];
+    assert_eq!(wyre!{ total::<u8, 3>(xs) }, 10);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
@@ -0,0 +1,126 @@
+use std::fmt::Display;
+
+// Check that every variable bound by a destructuring let or parameter gets
+// a node, with an edge from the destructured value.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+struct Multiple {
+    a: u64,
+    b: u64,
+}
+
+impl Display for Multiple {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.a, self.b)
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Pair(u64, u64);
+
+#[wye]
+fn add(m: Multiple) -> u64 {
+    let Multiple{a, b, ..} = m;
+    a + b
+}
+
+#[wye]
+fn mix((x, y): (u64, u64), Pair(p, _): Pair, &r: &u64) -> u64 {
+    let [first, .., last] = [x, y, p];
+    let (q, mut s) = (first * 2, last);
+    s += r;
+    q + s
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "m = 2 3" ]
+    1 [ label = "2" ]
+    2 [ label = "3" ]
+    3 [ label = "add(m) = 5" ]
+    4 [ label = "m = 2 3" ]
+    5 [ label = "a = 2" ]
+    6 [ label = "b = 3" ]
+    7 [ label = "+ = 5" ]
+    8 [ label = "mix((1, 2), Pair(3, 4), &one) = 6" ]
+    9 [ label = "(1, 2)" ]
+    10 [ label = "1" ]
+    11 [ label = "2" ]
+    12 [ label = "Pair(3, 4) = Pair(3, 4)" ]
+    13 [ label = "3" ]
+    14 [ label = "4" ]
+    15 [ label = "&one = 1" ]
+    16 [ label = "x = 1" ]
+    17 [ label = "y = 2" ]
+    18 [ label = "p = 3" ]
+    19 [ label = "r = 1" ]
+    20 [ label = "[1, 2, 3]" ]
+    21 [ label = "first = 1" ]
+    22 [ label = "last = 3" ]
+    23 [ label = "(2, 3)" ]
+    24 [ label = "* = 2" ]
+    25 [ label = "2" ]
+    26 [ label = "q = 2" ]
+    27 [ label = "s = 3" ]
+    28 [ label = "s = 4" ]
+    29 [ label = "+ = 6" ]
+    1 -> 0 [ label = "" ]
+    2 -> 0 [ label = "" ]
+    0 -> 4 [ label = "" ]
+    4 -> 5 [ label = "" ]
+    4 -> 6 [ label = "" ]
+    5 -> 7 [ label = "" ]
+    6 -> 7 [ label = "" ]
+    7 -> 3 [ label = "" ]
+    0 -> 3 [ label = "" ]
+    10 -> 9 [ label = "" ]
+    11 -> 9 [ label = "" ]
+    13 -> 12 [ label = "" ]
+    14 -> 12 [ label = "" ]
+    9 -> 16 [ label = "" ]
+    9 -> 17 [ label = "" ]
+    12 -> 18 [ label = "" ]
+    15 -> 19 [ label = "" ]
+    16 -> 20 [ label = "" ]
+    17 -> 20 [ label = "" ]
+    18 -> 20 [ label = "" ]
+    16 -> 21 [ label = "" ]
+    17 -> 21 [ label = "" ]
+    18 -> 21 [ label = "" ]
+    20 -> 21 [ label = "" ]
+    16 -> 22 [ label = "" ]
+    17 -> 22 [ label = "" ]
+    18 -> 22 [ label = "" ]
+    20 -> 22 [ label = "" ]
+    25 -> 24 [ label = "" ]
+    21 -> 24 [ label = "" ]
+    24 -> 23 [ label = "" ]
+    22 -> 23 [ label = "" ]
+    21 -> 26 [ label = "" ]
+    22 -> 26 [ label = "" ]
+    23 -> 26 [ label = "" ]
+    21 -> 27 [ label = "" ]
+    22 -> 27 [ label = "" ]
+    23 -> 27 [ label = "" ]
+    19 -> 28 [ label = "" ]
+    27 -> 28 [ label = "" ]
+    26 -> 29 [ label = "" ]
+    28 -> 29 [ label = "" ]
+    29 -> 8 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    assert_eq!(
+        wyre!{
+            let m = Multiple{a: 2, b: 3};
+            add(m)
+        }
+    , 5);
+    let one = 1;
+    assert_eq!(wyre!{ mix((1, 2), Pair(3, 4), &one) }, 6);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
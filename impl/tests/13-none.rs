@@ -9,6 +9,19 @@ fn none(a: u64, b: u64) -> Option<u64> {
 }
 
 const EXPECTED_GRAPH: &str = r#"digraph {
+    0 [ label = "none(1, 2).unwrap_or(3) = 3" ]
+    1 [ label = "none(1, 2) = None" ]
+    2 [ label = "1" ]
+    3 [ label = "2" ]
+    4 [ label = "a = 1" ]
+    5 [ label = "b = 2" ]
+    6 [ label = "maybe_val = None" ]
+    7 [ label = "3" ]
+    2 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
+    6 -> 1 [ label = "return" ]
+    1 -> 0 [ label = "" ]
+    7 -> 0 [ label = "" ]
 }
 "#;
 
@@ -0,0 +1,25 @@
+// Check that every node records where in the source it comes from.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[wye]
+fn add(a: u64, b: u64) -> u64 { a + b }
+
+pub fn main() {
+    assert_eq!(wyre!{add(1, 2)}, 3);
+    let trace = get_wye().trace();
+    let locations: Vec<(String, _, _, String)> = trace.nodes.iter().map(|node| {
+        let location = node.location.as_ref().unwrap();
+        assert!(location.file.ends_with("14-location.rs"), "{}", location.file);
+        (node.to_string(), location.start, location.end, location.text.to_string())
+    }).collect::<Vec<_>>();
+    eprintln!("{}", render::text(&trace));
+    assert_eq!(locations, vec![
+        ("add(1, 2) = 3".into(), (9, 22), (9, 31), "add(1, 2)".into()),
+        ("1".into(), (9, 26), (9, 27), "1".into()),
+        ("2".into(), (9, 29), (9, 30), "2".into()),
+        ("a = 1".into(), (6, 8), (6, 9), "a".into()),
+        ("b = 2".into(), (6, 16), (6, 17), "b".into()),
+        ("+ = 3".into(), (6, 33), (6, 38), "a + b".into()),
+    ]);
+}
@@ -0,0 +1,21 @@
+// Check that node slots are the stable site IDs of their locations.
+use wye::*;
+use std::collections::HashSet;
+
+#[wye]
+fn add(a: u64, b: u64) -> u64 { a + b }
+
+pub fn main() {
+    assert_eq!(wyre!{
+        let five = add(2, 3);
+        add(1, five)
+    }, 6);
+    let trace = get_wye().trace();
+    let mut slots = HashSet::new();
+    for node in &trace.nodes {
+        let location = node.location.as_ref().unwrap();
+        assert_eq!(node.slot, location.site_id(env!("CARGO_CRATE_NAME")), "{node} @ {location}");
+        slots.insert((node.frame, node.slot));
+    }
+    assert_eq!(slots.len(), trace.nodes.len());
+}
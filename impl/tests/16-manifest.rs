@@ -0,0 +1,40 @@
+// Check that the macros describe every site they instrument, executed or not.
+use wye::*;
+use wye::manifest::Manifest;
+use pretty_assertions::{assert_eq};
+
+#[wye]
+fn add(a: u64, b: u64) -> u64 { a + b }
+
+#[wye]
+#[allow(dead_code)]
+fn double(a: u64) -> u64 { a * 2 }
+
+pub fn main() {
+    assert_eq!(wyre!{add(1, 2)}, 3);
+    let manifest = Manifest::load_path(std::env::var("WYE_MANIFEST_DIR").unwrap()).unwrap();
+    let mut sites = manifest.sites.values()
+        .filter(|site| site.krate == env!("CARGO_CRATE_NAME") && site.file.ends_with("16-manifest.rs"))
+        .map(|site| (site.function.as_deref(), site.kind.as_str(), site.text.as_str()))
+        .collect::<Vec<_>>();
+    sites.sort();
+    assert_eq!(sites, vec![
+        (None, "Expr::Call", "add(1, 2)"),
+        (None, "Expr::Lit", "1"),
+        (None, "Expr::Lit", "2"),
+        (Some("add"), "Expr::Binary(+)", "a + b"),
+        (Some("add"), "Param", "a"),
+        (Some("add"), "Param", "b"),
+        (Some("double"), "Expr::Binary(*)", "a * 2"),
+        (Some("double"), "Expr::Lit", "2"),
+        (Some("double"), "Param", "a"),
+    ]);
+
+    let coverage = manifest.coverage(&get_wye().trace());
+    let mut never = coverage.sites.iter()
+        .filter(|(site, count)| site.krate == env!("CARGO_CRATE_NAME") && site.file.ends_with("16-manifest.rs") && *count == 0)
+        .map(|(site, _)| (site.function.as_deref(), site.text.as_str()))
+        .collect::<Vec<_>>();
+    never.sort();
+    assert_eq!(never, vec![(Some("double"), "2"), (Some("double"), "a"), (Some("double"), "a * 2")]);
+}
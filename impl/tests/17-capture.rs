@@ -0,0 +1,43 @@
+// Check that values are recorded whether or not their types are Debug.
+use wye::*;
+use wye::capture::Describe;
+use pretty_assertions::{assert_eq};
+
+struct OnlyDisplay(u64);
+
+impl std::fmt::Display for OnlyDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+struct Described(u64);
+
+impl Describe for Described {
+    fn describe(&self) -> String {
+        format!("described {}", self.0)
+    }
+}
+
+struct Opaque(u64);
+
+#[wye]
+fn sum(a: OnlyDisplay, b: Described, c: Opaque) -> u64 { a.0 + b.0 + c.0 }
+
+pub fn main() {
+    let a = OnlyDisplay(1);
+    let b = Described(2);
+    let c = Opaque(3);
+    assert_eq!(wyre!{sum(a, b, c)}, 6);
+    eprintln!("{}", get_wye());
+    let opaque = format!("<{}::Opaque; 8 bytes>", env!("CARGO_CRATE_NAME"));
+    let params = get_wye().trace().nodes.into_iter()
+        .filter(|node| node.function.as_deref() == Some("sum") && matches!(node.var.as_deref(), Some("a" | "b" | "c")))
+        .map(|node| (node.var.unwrap(), node.val))
+        .collect::<Vec<_>>();
+    assert_eq!(params, vec![
+        ("a".to_string(), "#1".to_string()),
+        ("b".to_string(), "described 2".to_string()),
+        ("c".to_string(), opaque),
+    ]);
+}
@@ -0,0 +1,49 @@
+// Check that #[wye(skip)] and #[wye::ignore] hide values behind opaque nodes
+// without breaking the causal path through them.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+struct Secret(u64);
+
+#[wye]
+fn add(#[wye(skip)] a: Secret, b: u64) -> u64 {
+    #[wye(skip)]
+    let key = a.0 * 2;
+    let sum = #[wye::ignore] { key + b };
+    sum
+}
+
+#[wye::ignore]
+fn helper() -> u64 { 1 }
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "secret = <skipped>" ]
+    1 [ label = "add(secret, one) = 3" ]
+    2 [ label = "one = 1" ]
+    3 [ label = "a = <skipped>" ]
+    4 [ label = "b = 1" ]
+    5 [ label = "key = <skipped>" ]
+    6 [ label = "sum = 3" ]
+    7 [ label = "{ key + b } = <skipped>" ]
+    0 -> 3 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
+    5 -> 7 [ label = "" ]
+    4 -> 7 [ label = "" ]
+    7 -> 6 [ label = "" ]
+    6 -> 1 [ label = "" ]
+    0 -> 1 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    let one = helper();
+    assert_eq!(wyre!{
+        #[wye::ignore]
+        let secret = Secret(1);
+        add(secret, one)
+    }, 3);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
@@ -0,0 +1,147 @@
+// Check that values deriving WyeValue are recorded with their components.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[derive(WyeValue)]
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+#[derive(WyeValue)]
+enum Shape {
+    Circle(Point, u64),
+    Square {
+        corner: Point,
+        #[wye(skip)]
+        #[allow(dead_code)]
+        side: u64,
+        #[wye(redact)]
+        #[allow(dead_code)]
+        owner: String,
+    },
+}
+
+struct Area(u64);
+
+impl WyeValue for Area {
+    fn label(&self) -> String {
+        format!("{} square units", self.0)
+    }
+
+    fn long(&self) -> Option<String> {
+        Some(format!("an area of {} square units", self.0))
+    }
+}
+
+#[wye]
+fn area(shape: Shape) -> Area {
+    Area(match shape { Shape::Circle(_, r) => 3 * r * r, Shape::Square{side, ..} => side * side })
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "area(square) = 9 square units" ]
+    1 [ label = "square = Shape::Square" ]
+    2 [ label = "square.corner = Point" ]
+    3 [ label = "square.corner.x = 1" ]
+    4 [ label = "square.corner.y = 2" ]
+    5 [ label = "square.owner = <redacted>" ]
+    6 [ label = "shape = Shape::Square" ]
+    7 [ label = "shape.corner = Point" ]
+    8 [ label = "shape.corner.x = 1" ]
+    9 [ label = "shape.corner.y = 2" ]
+    10 [ label = "shape.owner = <redacted>" ]
+    11 [ label = "Area(match shape { Shape::Circle(_, r) => 3 * r * r, Shape::Square{side, ..} => side * side }) = 9 square units" ]
+    12 [ label = "shape = Shape::Square" ]
+    13 [ label = "shape.corner = Point" ]
+    14 [ label = "shape.corner.x = 1" ]
+    15 [ label = "shape.corner.y = 2" ]
+    16 [ label = "shape.owner = <redacted>" ]
+    17 [ label = "side = 3" ]
+    18 [ label = "Shape::Square{side, ..} = 9" ]
+    19 [ label = "* = 9" ]
+    20 [ label = "area(circle) = 12 square units" ]
+    21 [ label = "circle = Shape::Circle" ]
+    22 [ label = "circle.0 = Point" ]
+    23 [ label = "circle.0.x = 0" ]
+    24 [ label = "circle.0.y = 0" ]
+    25 [ label = "circle.1 = 2" ]
+    26 [ label = "shape = Shape::Circle" ]
+    27 [ label = "shape.0 = Point" ]
+    28 [ label = "shape.0.x = 0" ]
+    29 [ label = "shape.0.y = 0" ]
+    30 [ label = "shape.1 = 2" ]
+    31 [ label = "Area(match shape { Shape::Circle(_, r) => 3 * r * r, Shape::Square{side, ..} => side * side }) = 12 square units" ]
+    32 [ label = "shape = Shape::Circle" ]
+    33 [ label = "shape.0 = Point" ]
+    34 [ label = "shape.0.x = 0" ]
+    35 [ label = "shape.0.y = 0" ]
+    36 [ label = "shape.1 = 2" ]
+    37 [ label = "r = 2" ]
+    38 [ label = "Shape::Circle(_, r) = 12" ]
+    39 [ label = "* = 12" ]
+    40 [ label = "* = 6" ]
+    41 [ label = "3" ]
+    1 -> 2 [ label = "corner" ]
+    2 -> 3 [ label = "x" ]
+    2 -> 4 [ label = "y" ]
+    1 -> 5 [ label = "owner" ]
+    6 -> 7 [ label = "corner" ]
+    7 -> 8 [ label = "x" ]
+    7 -> 9 [ label = "y" ]
+    6 -> 10 [ label = "owner" ]
+    1 -> 6 [ label = "" ]
+    12 -> 13 [ label = "corner" ]
+    13 -> 14 [ label = "x" ]
+    13 -> 15 [ label = "y" ]
+    12 -> 16 [ label = "owner" ]
+    6 -> 12 [ label = "" ]
+    12 -> 17 [ label = "" ]
+    12 -> 19 [ label = "control" ]
+    17 -> 19 [ label = "" ]
+    12 -> 18 [ label = "control" ]
+    19 -> 18 [ label = "" ]
+    6 -> 11 [ label = "" ]
+    18 -> 11 [ label = "" ]
+    11 -> 0 [ label = "" ]
+    21 -> 22 [ label = "0" ]
+    22 -> 23 [ label = "x" ]
+    22 -> 24 [ label = "y" ]
+    21 -> 25 [ label = "1" ]
+    26 -> 27 [ label = "0" ]
+    27 -> 28 [ label = "x" ]
+    27 -> 29 [ label = "y" ]
+    26 -> 30 [ label = "1" ]
+    21 -> 26 [ label = "" ]
+    32 -> 33 [ label = "0" ]
+    33 -> 34 [ label = "x" ]
+    33 -> 35 [ label = "y" ]
+    32 -> 36 [ label = "1" ]
+    26 -> 32 [ label = "" ]
+    32 -> 37 [ label = "" ]
+    32 -> 41 [ label = "control" ]
+    32 -> 40 [ label = "control" ]
+    41 -> 40 [ label = "" ]
+    37 -> 40 [ label = "" ]
+    32 -> 39 [ label = "control" ]
+    40 -> 39 [ label = "" ]
+    37 -> 39 [ label = "" ]
+    32 -> 38 [ label = "control" ]
+    39 -> 38 [ label = "" ]
+    26 -> 31 [ label = "" ]
+    38 -> 31 [ label = "" ]
+    31 -> 20 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    let square = Shape::Square{corner: Point{x: 1, y: 2}, side: 3, owner: "ann".into()};
+    assert_eq!(wyre!{area(square)}.0, 9);
+    let circle = Shape::Circle(Point{x: 0, y: 0}, 2);
+    assert_eq!(wyre!{area(circle)}.0, 12);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+    let trace = get_wye().trace();
+    assert_eq!(trace.nodes[0].long.as_deref(), Some("an area of 9 square units"));
+}
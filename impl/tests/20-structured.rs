@@ -0,0 +1,42 @@
+// Check that Serialize values are recorded in structured form and can be queried.
+use wye::*;
+use pretty_assertions::{assert_eq};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+}
+
+#[wye]
+fn respond(status: u16) -> Response {
+    Response{status, headers: vec![("server".into(), "wye".into())]}
+}
+
+pub fn main() {
+    wyre!{respond(200)};
+    wyre!{respond(500)};
+    eprintln!("{}", get_wye());
+    let trace = get_wye().trace();
+    // The call and the struct expression in its body.
+    let failed = trace.select(".status == 500");
+    assert_eq!(failed.len(), 2);
+    for id in failed {
+        assert_eq!(trace.nodes[id].data, Some(serde_json::json!({
+            "status": 500,
+            "headers": [["server", "wye"]],
+        })));
+    }
+    assert_eq!(trace.select(".headers.0.1 == wye").len(), 4);
+    assert_eq!(trace.select(".headers.0.1 == \"wye\""), trace.select(".headers.0.1 == wye"));
+    // The literal argument and the parameter it binds.
+    let whole = trace.select(". == 500").into_iter()
+        .map(|id| (trace.nodes[id].var.as_deref(), trace.nodes[id].val.as_str(), trace.nodes[id].data.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(whole, vec![
+        (None, "500", Some(serde_json::json!(500))),
+        (Some("status"), "500", Some(serde_json::json!(500))),
+    ]);
+    assert!(trace.select(".missing").is_empty());
+}
@@ -0,0 +1,94 @@
+// Check that the logger's policy redacts and truncates recorded values,
+// including those computed from redacted variables.
+use wye::*;
+use wye::policy::Policy;
+use pretty_assertions::{assert_eq};
+
+#[derive(WyeValue)]
+struct Login {
+    user: String,
+    password: String,
+}
+
+#[wye]
+fn login(user: String, password: String, key: Redacted<u64>) -> Login {
+    let checked = *key + 1;
+    Login{user: format!("{user}{checked}"), password}
+}
+
+#[wye]
+fn strength(secret: &str) -> usize {
+    secret.len()
+}
+
+#[wye]
+fn check(password: String) -> usize {
+    strength(&password) + strength(password.trim())
+}
+
+#[wye]
+fn count(values: Vec<u64>) -> usize {
+    values.len()
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "login(user, password, key) = Login" ]
+    1 [ label = "user = \"ann\"" ]
+    2 [ label = "password = <redacted>" ]
+    3 [ label = "key = <redacted>" ]
+    4 [ label = "user = \"ann\"" ]
+    5 [ label = "password = <redacted>" ]
+    6 [ label = "key = <redacted>" ]
+    7 [ label = "checked = 3" ]
+    8 [ label = "2" ]
+    9 [ label = "1" ]
+    10 [ label = "<redacted>" ]
+    11 [ label = "format!(\"{user}{checked}\") = \"ann3\"" ]
+    12 [ label = ".user = \"ann3\"" ]
+    13 [ label = ".password = <redacted>" ]
+    14 [ label = "login(user, password, key).user = \"ann3\"" ]
+    15 [ label = "login(user, password, key).password = <redacted>" ]
+    1 -> 4 [ label = "" ]
+    2 -> 5 [ label = "" ]
+    3 -> 6 [ label = "" ]
+    6 -> 8 [ label = "" ]
+    8 -> 7 [ label = "" ]
+    9 -> 7 [ label = "" ]
+    4 -> 11 [ label = "" ]
+    7 -> 11 [ label = "" ]
+    10 -> 12 [ label = "user" ]
+    10 -> 13 [ label = "password" ]
+    11 -> 10 [ label = "" ]
+    4 -> 10 [ label = "" ]
+    5 -> 10 [ label = "" ]
+    0 -> 14 [ label = "user" ]
+    0 -> 15 [ label = "password" ]
+    10 -> 0 [ label = "" ]
+}"#;
+
+pub fn main() {
+    let user = "ann".to_string();
+    let password = "hunter2".to_string();
+    let key = Redacted(2);
+    wyre!{login(user, password, key)};
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+    assert!(!format!("{:?}", get_wye().trace()).contains("hunter2"));
+
+    let password = "hunter2 ".to_string();
+    assert_eq!(wyre!{check(password)}, 15);
+    let trace = get_wye().trace();
+    assert!(!format!("{:?}", trace).contains("hunter2"));
+    for var in ["&password", "password.trim()", "secret", "secret.len()"] {
+        let node = trace.nodes.iter().find(|node| node.var.as_deref() == Some(var)).unwrap();
+        assert_eq!(node.val, wye::policy::REDACTED, "{var}");
+    }
+
+    get_wye().set_policy(Policy{max_len: Some(16), ..Policy::default()});
+    let values = vec![7; 1000];
+    assert_eq!(wyre!{count(values)}, 1000);
+    let trace = get_wye().trace();
+    let values = trace.nodes.iter().find(|node| node.var.as_deref() == Some("values")).unwrap();
+    assert_eq!(values.val, "[7, 7, 7, 7, 7, ...<2984 more bytes>");
+}
@@ -0,0 +1,68 @@
+// Check that method calls record their receivers and arguments like calls.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+struct Pair {
+    left: Vec<u64>,
+}
+
+#[wye]
+fn double(a: u64) -> u64 { a * 2 }
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "v = [1]" ]
+    1 [ label = "pair.left.clone() = [1]" ]
+    2 [ label = "pair.left = [1]" ]
+    3 [ label = "v.push(double(2)) = ()" ]
+    4 [ label = "v = [1]" ]
+    5 [ label = "double(2) = 4" ]
+    6 [ label = "2" ]
+    7 [ label = "a = 2" ]
+    8 [ label = "* = 4" ]
+    9 [ label = "2" ]
+    10 [ label = "sep = \"2\"" ]
+    11 [ label = "v.len().to_string() = \"2\"" ]
+    12 [ label = "v.len() = 2" ]
+    13 [ label = "v = [1, 4]" ]
+    14 [ label = "words.join(&sep) = \"a2b\"" ]
+    15 [ label = "words = [\"a\", \"b\"]" ]
+    16 [ label = "&sep = \"2\"" ]
+    2 -> 1 [ label = "" ]
+    1 -> 0 [ label = "" ]
+    0 -> 4 [ label = "" ]
+    6 -> 7 [ label = "" ]
+    9 -> 8 [ label = "" ]
+    7 -> 8 [ label = "" ]
+    8 -> 5 [ label = "" ]
+    0 -> 3 [ label = "" ]
+    4 -> 3 [ label = "" ]
+    5 -> 3 [ label = "" ]
+    0 -> 13 [ label = "" ]
+    0 -> 12 [ label = "" ]
+    13 -> 12 [ label = "" ]
+    0 -> 11 [ label = "" ]
+    12 -> 11 [ label = "" ]
+    11 -> 10 [ label = "" ]
+    10 -> 16 [ label = "" ]
+    10 -> 14 [ label = "" ]
+    15 -> 14 [ label = "" ]
+    16 -> 14 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    let pair = Pair{left: vec![1]};
+    let words = vec!["a", "b"];
+    assert_eq!(wyre!{
+        let mut v = pair.left.clone();
+        // v is not versioned by push, so v.len() gets its edge from the
+        // v bound above, not from the call, though it records [1, 4]:
+        v.push(double(2));
+        let sep = v.len().to_string();
+        words.join(&sep)
+    }, "a2b");
+    assert_eq!(pair.left, [1]);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
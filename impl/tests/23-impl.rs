@@ -0,0 +1,124 @@
+// Check that #[wye] on impl blocks and traits instruments methods, with
+// self as a source.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[derive(Debug)]
+struct Counter {
+    count: u64,
+}
+
+#[wye]
+impl Counter {
+    fn new(count: u64) -> Self {
+        Counter{count}
+    }
+
+    fn incr(&mut self, by: u64) -> u64 {
+        self.count += by;
+        self.count
+    }
+
+    fn get(&self) -> u64 {
+        self.count
+    }
+}
+
+#[wye]
+trait Shape {
+    fn sides(&self) -> u64;
+
+    fn describe(&self) -> String {
+        format!("{} sides", self.sides())
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Square(u64);
+
+#[wye]
+impl Shape for Square {
+    fn sides(&self) -> u64 {
+        4
+    }
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "counter = Counter { count: 1 }" ]
+    1 [ label = "Counter::new(1) = Counter { count: 1 }" ]
+    2 [ label = "1" ]
+    3 [ label = "count = 1" ]
+    4 [ label = "Counter { count: 1 }" ]
+    5 [ label = "counter.incr(2) = 3" ]
+    6 [ label = "counter = Counter { count: 1 }" ]
+    7 [ label = "2" ]
+    8 [ label = "self = Counter { count: 1 }" ]
+    9 [ label = "by = 2" ]
+    10 [ label = "self = Counter { count: 3 }" ]
+    11 [ label = "3" ]
+    12 [ label = "self = Counter { count: 3 }" ]
+    13 [ label = "sides = \"4 sides\"" ]
+    14 [ label = "square.describe() = \"4 sides\"" ]
+    15 [ label = "square = <alloc::boxed::Box<dyn {CRATE}::Shape>; {BOX_SIZE} bytes>" ]
+    16 [ label = "self = <&{CRATE}::Square; {REF_SIZE} bytes>" ]
+    17 [ label = "format!(\"{} sides\", self.sides()) = \"4 sides\"" ]
+    18 [ label = "self.sides() = 4" ]
+    19 [ label = "self = <&{CRATE}::Square; {REF_SIZE} bytes>" ]
+    20 [ label = "self = Square(2)" ]
+    21 [ label = "4" ]
+    22 [ label = "format!(\"{} {}\", counter.get(), sides) = \"3 4 sides\"" ]
+    23 [ label = "counter.get() = 3" ]
+    24 [ label = "counter = Counter { count: 3 }" ]
+    25 [ label = "self = Counter { count: 3 }" ]
+    26 [ label = "3" ]
+    2 -> 3 [ label = "" ]
+    3 -> 4 [ label = "" ]
+    4 -> 1 [ label = "" ]
+    1 -> 0 [ label = "" ]
+    0 -> 6 [ label = "" ]
+    6 -> 8 [ label = "" ]
+    7 -> 9 [ label = "" ]
+    9 -> 10 [ label = "" ]
+    8 -> 10 [ label = "" ]
+    10 -> 11 [ label = "" ]
+    10 -> 12 [ label = "" ]
+    11 -> 5 [ label = "" ]
+    0 -> 5 [ label = "" ]
+    15 -> 16 [ label = "" ]
+    16 -> 19 [ label = "" ]
+    19 -> 20 [ label = "" ]
+    21 -> 18 [ label = "" ]
+    16 -> 18 [ label = "" ]
+    18 -> 17 [ label = "" ]
+    17 -> 14 [ label = "" ]
+    14 -> 13 [ label = "" ]
+    12 -> 24 [ label = "" ]
+    24 -> 25 [ label = "" ]
+    25 -> 26 [ label = "" ]
+    26 -> 23 [ label = "" ]
+    12 -> 23 [ label = "" ]
+    23 -> 22 [ label = "" ]
+    13 -> 22 [ label = "" ]
+}"#;
+
+pub fn main() {
+    let square: Box<dyn Shape> = Box::new(Square(2));
+    assert_eq!(wyre!{
+        let mut counter = Counter::new(1);
+        counter.incr(2);
+        let sides = square.describe();
+        format!("{} {}", counter.get(), sides)
+    }, "3 4 sides");
+    eprintln!("{}", get_wye());
+    let expected = EXPECTED_GRAPH.replace("{CRATE}", env!("CARGO_CRATE_NAME"))
+        .replace("{BOX_SIZE}", &std::mem::size_of::<Box<dyn Shape>>().to_string())
+        .replace("{REF_SIZE}", &std::mem::size_of::<&Square>().to_string());
+    assert_eq!(format!("{}", get_wye()).trim(), expected.trim());
+    let functions = get_wye().trace().nodes.into_iter()
+        .filter(|node| node.var.as_deref() == Some("self"))
+        .map(|node| node.function.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(functions, ["Counter::incr", "Counter::incr", "Counter::incr", "Shape::describe", "Shape::describe", "Square::sides", "Counter::get"]);
+}
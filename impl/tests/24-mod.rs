@@ -0,0 +1,56 @@
+// Check that #[wye] on a module instruments the functions and methods in
+// it and in its submodules, except those filtered out or marked skipped.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[wye(exclude: ["app::debug_*", "app::Meter::reset"])]
+mod app {
+    pub struct Meter(pub u64);
+
+    impl Meter {
+        pub fn read(&self) -> u64 {
+            self.0
+        }
+
+        pub fn reset(&mut self) {
+            self.0 = 0;
+        }
+    }
+
+    pub fn run(meter: &Meter) -> u64 {
+        let sent = net::send(meter.read());
+        sent
+    }
+
+    pub fn debug_dump(n: u64) -> u64 {
+        n
+    }
+
+    pub mod net {
+        pub fn send(n: u64) -> u64 {
+            n + 1
+        }
+
+        #[wye(skip)]
+        pub fn ping(n: u64) -> u64 {
+            n
+        }
+    }
+}
+
+pub fn main() {
+    let meter = app::Meter(41);
+    assert_eq!(wyre!{
+        app::run(&meter)
+    }, 42);
+    let mut meter = meter;
+    meter.reset();
+    assert_eq!(app::debug_dump(app::net::ping(meter.0)), 0);
+    eprintln!("{}", get_wye());
+    let mut functions = get_wye().trace().nodes.into_iter()
+        .filter_map(|node| node.function)
+        .collect::<Vec<_>>();
+    functions.sort();
+    functions.dedup();
+    assert_eq!(functions, ["app::Meter::read", "app::net::send", "app::run"]);
+}
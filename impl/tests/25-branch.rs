@@ -0,0 +1,142 @@
+// Check that ifs and matches record their conditions, the branches they
+// take, and the variables those branches bind, with control edges from the
+// conditions to the values computed in the branches.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[derive(Debug)]
+#[allow(dead_code)]
+enum Shape {
+    Square(u64),
+    Rect{w: u64, h: u64},
+    Empty,
+}
+
+#[wye]
+fn area(shape: Shape) -> u64 {
+    match shape {
+        Shape::Square(s) => s * s,
+        Shape::Rect{w, h} => w * h,
+        Shape::Empty => 0,
+    }
+}
+
+#[wye]
+fn clamp(x: u64, max: u64) -> u64 {
+    if x > max { max } else { x }
+}
+
+#[wye]
+fn first_or(xs: &[u64], default: u64) -> u64 {
+    if let Some(first) = xs.first() { *first } else { default }
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "area(Shape::Rect{w: 2, h: 3}) = 6" ]
+    1 [ label = "Rect { w: 2, h: 3 }" ]
+    2 [ label = "2" ]
+    3 [ label = "3" ]
+    4 [ label = "shape = Rect { w: 2, h: 3 }" ]
+    5 [ label = "shape = Rect { w: 2, h: 3 }" ]
+    6 [ label = "w = 2" ]
+    7 [ label = "h = 3" ]
+    8 [ label = "Shape::Rect{w, h} = 6" ]
+    9 [ label = "* = 6" ]
+    10 [ label = "clamp(7, 5) = 5" ]
+    11 [ label = "7" ]
+    12 [ label = "5" ]
+    13 [ label = "x = 7" ]
+    14 [ label = "max = 5" ]
+    15 [ label = "> = true" ]
+    16 [ label = "then = 5" ]
+    17 [ label = "first_or(&empty, 4) = 4" ]
+    18 [ label = "&empty = []" ]
+    19 [ label = "4" ]
+    20 [ label = "xs = []" ]
+    21 [ label = "default = 4" ]
+    22 [ label = "xs.first() = None" ]
+    23 [ label = "xs = []" ]
+    24 [ label = "else = 4" ]
+    25 [ label = "y = 3" ]
+    26 [ label = "opt = Some(2)" ]
+    27 [ label = "n = 2" ]
+    28 [ label = "Some(n) = 3" ]
+    29 [ label = "+ = 3" ]
+    30 [ label = "1" ]
+    31 [ label = "z = 0" ]
+    32 [ label = "> = true" ]
+    33 [ label = "2" ]
+    34 [ label = "then = ()" ]
+    35 [ label = "z = 3" ]
+    2 -> 1 [ label = "" ]
+    3 -> 1 [ label = "" ]
+    1 -> 4 [ label = "" ]
+    4 -> 5 [ label = "" ]
+    5 -> 6 [ label = "" ]
+    5 -> 7 [ label = "" ]
+    5 -> 9 [ label = "control" ]
+    6 -> 9 [ label = "" ]
+    7 -> 9 [ label = "" ]
+    5 -> 8 [ label = "control" ]
+    9 -> 8 [ label = "" ]
+    8 -> 0 [ label = "" ]
+    11 -> 13 [ label = "" ]
+    12 -> 14 [ label = "" ]
+    13 -> 15 [ label = "" ]
+    14 -> 15 [ label = "" ]
+    15 -> 16 [ label = "control" ]
+    14 -> 16 [ label = "" ]
+    16 -> 10 [ label = "" ]
+    18 -> 20 [ label = "" ]
+    19 -> 21 [ label = "" ]
+    20 -> 23 [ label = "" ]
+    20 -> 22 [ label = "" ]
+    23 -> 22 [ label = "" ]
+    22 -> 24 [ label = "control" ]
+    21 -> 24 [ label = "" ]
+    24 -> 17 [ label = "" ]
+    26 -> 27 [ label = "" ]
+    26 -> 30 [ label = "control" ]
+    26 -> 29 [ label = "control" ]
+    30 -> 29 [ label = "" ]
+    27 -> 29 [ label = "" ]
+    26 -> 28 [ label = "control" ]
+    29 -> 28 [ label = "" ]
+    28 -> 25 [ label = "" ]
+    33 -> 32 [ label = "" ]
+    25 -> 32 [ label = "" ]
+    32 -> 35 [ label = "control" ]
+    25 -> 35 [ label = "" ]
+    31 -> 35 [ label = "" ]
+    32 -> 34 [ label = "control" ]
+}
+"#;
+
+pub fn main() {
+    assert_eq!(wyre!{
+        area(Shape::Rect{w: 2, h: 3})
+    }, 6);
+    assert_eq!(wyre!{
+        clamp(7, 5)
+    }, 5);
+    let empty = vec![];
+    assert_eq!(wyre!{
+        first_or(&empty, 4)
+    }, 4);
+    let opt = Some(2);
+    assert_eq!(wyre!{
+        let y = match opt {
+            Some(n) if n > 1 => n + 1,
+            Some(_) => 1,
+            None => 0,
+        };
+        let mut z = 0;
+        if y > 2 {
+            z = y;
+        }
+        z
+    }, 3);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
@@ -0,0 +1,211 @@
+// Check that each iteration of a loop gets nodes of its own, that values
+// carried from one iteration to the next, like accumulators and items, get
+// edges from the previous iteration's, and that long loops are summarized.
+use wye::*;
+use wye::policy::Policy;
+use pretty_assertions::{assert_eq};
+
+#[wye]
+fn sum(xs: &[u64]) -> u64 {
+    let mut total = 0;
+    for x in xs {
+        total += x;
+    }
+    total
+}
+
+#[wye]
+fn countdown(mut n: u64) -> u64 {
+    let mut steps = 0;
+    while n > 0 {
+        n -= 1;
+        steps += 1;
+    }
+    steps
+}
+
+#[wye]
+fn drain(mut stack: Vec<u64>) -> u64 {
+    let mut last = 0;
+    while let Some(top) = stack.pop() {
+        last = top;
+    }
+    last
+}
+
+#[wye]
+fn root_above(limit: u64) -> u64 {
+    let mut i = 0;
+    loop {
+        i += 1;
+        if i * i > limit {
+            break i;
+        }
+    }
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "sum(&xs) = 3" ]
+    1 [ label = "&xs = [1, 2]" ]
+    2 [ label = "xs = [1, 2]" ]
+    3 [ label = "total = 0" ]
+    4 [ label = "xs = [1, 2]" ]
+    5 [ label = "x = 1" ]
+    6 [ label = "total = 1" ]
+    7 [ label = "x = 2" ]
+    8 [ label = "total = 3" ]
+    9 [ label = "countdown(2) = 2" ]
+    10 [ label = "2" ]
+    11 [ label = "n = 2" ]
+    12 [ label = "steps = 0" ]
+    13 [ label = "> = true" ]
+    14 [ label = "0" ]
+    15 [ label = "1" ]
+    16 [ label = "n = 1" ]
+    17 [ label = "1" ]
+    18 [ label = "steps = 1" ]
+    19 [ label = "> = true" ]
+    20 [ label = "0" ]
+    21 [ label = "1" ]
+    22 [ label = "n = 0" ]
+    23 [ label = "1" ]
+    24 [ label = "steps = 2" ]
+    25 [ label = "> = false" ]
+    26 [ label = "0" ]
+    27 [ label = "drain(vec![5, 6]) = 5" ]
+    28 [ label = "vec![5, 6] = [5, 6]" ]
+    29 [ label = "stack = [5, 6]" ]
+    30 [ label = "last = 0" ]
+    31 [ label = "stack.pop() = Some(6)" ]
+    32 [ label = "stack = [5, 6]" ]
+    33 [ label = "top = 6" ]
+    34 [ label = "last = 6" ]
+    35 [ label = "stack.pop() = Some(5)" ]
+    36 [ label = "stack = [5]" ]
+    37 [ label = "top = 5" ]
+    38 [ label = "last = 5" ]
+    39 [ label = "stack.pop() = None" ]
+    40 [ label = "stack = []" ]
+    41 [ label = "root_above(3) = 2" ]
+    42 [ label = "3" ]
+    43 [ label = "limit = 3" ]
+    44 [ label = "i = 0" ]
+    45 [ label = "1" ]
+    46 [ label = "i = 1" ]
+    47 [ label = "> = false" ]
+    48 [ label = "* = 1" ]
+    49 [ label = "1" ]
+    50 [ label = "i = 2" ]
+    51 [ label = "> = true" ]
+    52 [ label = "* = 4" ]
+    53 [ label = "sum(&ys) = 15" ]
+    54 [ label = "&ys = [1, 2, 3, 4, 5]" ]
+    55 [ label = "xs = [1, 2, 3, 4, 5]" ]
+    56 [ label = "total = 0" ]
+    57 [ label = "xs = [1, 2, 3, 4, 5]" ]
+    58 [ label = "x = 1" ]
+    59 [ label = "total = 1" ]
+    60 [ label = "x = 2" ]
+    61 [ label = "total = 3" ]
+    62 [ label = "<3 more iterations>" ]
+    1 -> 2 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    4 -> 5 [ label = "" ]
+    5 -> 6 [ label = "" ]
+    3 -> 6 [ label = "" ]
+    4 -> 7 [ label = "" ]
+    5 -> 7 [ label = "" ]
+    7 -> 8 [ label = "" ]
+    6 -> 8 [ label = "" ]
+    8 -> 0 [ label = "" ]
+    10 -> 11 [ label = "" ]
+    14 -> 13 [ label = "" ]
+    11 -> 13 [ label = "" ]
+    13 -> 15 [ label = "control" ]
+    13 -> 16 [ label = "control" ]
+    15 -> 16 [ label = "" ]
+    11 -> 16 [ label = "" ]
+    13 -> 17 [ label = "control" ]
+    13 -> 18 [ label = "control" ]
+    17 -> 18 [ label = "" ]
+    12 -> 18 [ label = "" ]
+    20 -> 19 [ label = "" ]
+    16 -> 19 [ label = "" ]
+    19 -> 21 [ label = "control" ]
+    19 -> 22 [ label = "control" ]
+    21 -> 22 [ label = "" ]
+    16 -> 22 [ label = "" ]
+    19 -> 23 [ label = "control" ]
+    19 -> 24 [ label = "control" ]
+    23 -> 24 [ label = "" ]
+    18 -> 24 [ label = "" ]
+    26 -> 25 [ label = "" ]
+    22 -> 25 [ label = "" ]
+    24 -> 9 [ label = "" ]
+    28 -> 29 [ label = "" ]
+    29 -> 32 [ label = "" ]
+    29 -> 31 [ label = "" ]
+    32 -> 31 [ label = "" ]
+    31 -> 33 [ label = "" ]
+    31 -> 34 [ label = "control" ]
+    33 -> 34 [ label = "" ]
+    30 -> 34 [ label = "" ]
+    29 -> 36 [ label = "" ]
+    29 -> 35 [ label = "" ]
+    36 -> 35 [ label = "" ]
+    35 -> 37 [ label = "" ]
+    33 -> 37 [ label = "" ]
+    35 -> 38 [ label = "control" ]
+    37 -> 38 [ label = "" ]
+    34 -> 38 [ label = "" ]
+    29 -> 40 [ label = "" ]
+    29 -> 39 [ label = "" ]
+    40 -> 39 [ label = "" ]
+    38 -> 27 [ label = "" ]
+    42 -> 43 [ label = "" ]
+    45 -> 46 [ label = "" ]
+    44 -> 46 [ label = "" ]
+    46 -> 48 [ label = "" ]
+    48 -> 47 [ label = "" ]
+    43 -> 47 [ label = "" ]
+    49 -> 50 [ label = "" ]
+    46 -> 50 [ label = "" ]
+    50 -> 52 [ label = "" ]
+    52 -> 51 [ label = "" ]
+    43 -> 51 [ label = "" ]
+    50 -> 41 [ label = "break" ]
+    54 -> 55 [ label = "" ]
+    55 -> 57 [ label = "" ]
+    57 -> 58 [ label = "" ]
+    58 -> 59 [ label = "" ]
+    56 -> 59 [ label = "" ]
+    57 -> 60 [ label = "" ]
+    58 -> 60 [ label = "" ]
+    60 -> 61 [ label = "" ]
+    59 -> 61 [ label = "" ]
+    57 -> 62 [ label = "" ]
+    60 -> 62 [ label = "" ]
+    61 -> 62 [ label = "" ]
+    62 -> 53 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    let xs = vec![1, 2];
+    assert_eq!(wyre!{ sum(&xs) }, 3);
+    assert_eq!(wyre!{ countdown(2) }, 2);
+    assert_eq!(wyre!{ drain(vec![5, 6]) }, 5);
+    assert_eq!(wyre!{ root_above(3) }, 2);
+
+    // iterations after the first max_iterations are summarized:
+    get_wye().set_policy(Policy{max_iterations: Some(2), ..Policy::default()});
+    let ys = vec![1, 2, 3, 4, 5];
+    assert_eq!(wyre!{ sum(&ys) }, 15);
+    let trace = get_wye().trace();
+    assert!(trace.nodes.iter().any(|node| node.val == "<3 more iterations>"));
+    assert!(trace.nodes.iter().any(|node| node.iterations == [1]));
+
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
@@ -0,0 +1,137 @@
+// Check that each assignment records a new version of the variable it
+// assigns to, with edges from its inputs and from the previous version, and
+// that later uses get edges from the version live when they run.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[derive(Debug)]
+struct Point { x: u64, y: u64 }
+
+#[wye]
+fn bump(a: u64, b: u64) -> u64 {
+    let mut x = a;
+    x = x + b;
+    x *= 2;
+    x + 1
+}
+
+#[wye]
+fn shift(mut p: Point, by: u64) -> u64 {
+    p.y += by;
+    p.x + p.y
+}
+
+#[wye]
+fn set(mut v: Vec<u64>, i: usize) -> u64 {
+    v[i] = 9;
+    v[0] + v[1]
+}
+
+#[wye]
+fn pick(flag: bool) -> u64 {
+    let mut n = 1;
+    if flag {
+        n = 5;
+    }
+    n
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "bump(1, 2) = 7" ]
+    1 [ label = "1" ]
+    2 [ label = "2" ]
+    3 [ label = "a = 1" ]
+    4 [ label = "b = 2" ]
+    5 [ label = "x = 1" ]
+    6 [ label = "+ = 3" ]
+    7 [ label = "x = 3" ]
+    8 [ label = "2" ]
+    9 [ label = "x = 6" ]
+    10 [ label = "+ = 7" ]
+    11 [ label = "1" ]
+    12 [ label = "shift(Point{x: 1, y: 2}, 3) = 6" ]
+    13 [ label = "Point { x: 1, y: 2 }" ]
+    14 [ label = "1" ]
+    15 [ label = "2" ]
+    16 [ label = "3" ]
+    17 [ label = "p = Point { x: 1, y: 2 }" ]
+    18 [ label = "by = 3" ]
+    19 [ label = "p = Point { x: 1, y: 5 }" ]
+    20 [ label = "+ = 6" ]
+    21 [ label = "1" ]
+    22 [ label = "5" ]
+    23 [ label = "set(vec![1, 2], 0) = 11" ]
+    24 [ label = "vec![1, 2] = [1, 2]" ]
+    25 [ label = "0" ]
+    26 [ label = "v = [1, 2]" ]
+    27 [ label = "i = 0" ]
+    28 [ label = "9" ]
+    29 [ label = "v = [9, 2]" ]
+    30 [ label = "+ = 11" ]
+    31 [ label = "9" ]
+    32 [ label = "0" ]
+    33 [ label = "2" ]
+    34 [ label = "1" ]
+    35 [ label = "pick(true) = 5" ]
+    36 [ label = "true" ]
+    37 [ label = "flag = true" ]
+    38 [ label = "n = 1" ]
+    39 [ label = "flag = true" ]
+    40 [ label = "then = ()" ]
+    41 [ label = "5" ]
+    42 [ label = "n = 5" ]
+    1 -> 3 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
+    5 -> 6 [ label = "" ]
+    4 -> 6 [ label = "" ]
+    6 -> 7 [ label = "" ]
+    5 -> 7 [ label = "" ]
+    8 -> 9 [ label = "" ]
+    7 -> 9 [ label = "" ]
+    11 -> 10 [ label = "" ]
+    9 -> 10 [ label = "" ]
+    10 -> 0 [ label = "" ]
+    14 -> 13 [ label = "" ]
+    15 -> 13 [ label = "" ]
+    13 -> 17 [ label = "" ]
+    16 -> 18 [ label = "" ]
+    18 -> 19 [ label = "" ]
+    17 -> 19 [ label = "" ]
+    19 -> 21 [ label = "" ]
+    19 -> 22 [ label = "" ]
+    21 -> 20 [ label = "" ]
+    22 -> 20 [ label = "" ]
+    20 -> 12 [ label = "" ]
+    24 -> 26 [ label = "" ]
+    25 -> 27 [ label = "" ]
+    28 -> 29 [ label = "" ]
+    27 -> 29 [ label = "" ]
+    26 -> 29 [ label = "" ]
+    32 -> 31 [ label = "" ]
+    29 -> 31 [ label = "" ]
+    34 -> 33 [ label = "" ]
+    29 -> 33 [ label = "" ]
+    31 -> 30 [ label = "" ]
+    33 -> 30 [ label = "" ]
+    30 -> 23 [ label = "" ]
+    36 -> 37 [ label = "" ]
+    37 -> 39 [ label = "" ]
+    39 -> 41 [ label = "control" ]
+    39 -> 42 [ label = "control" ]
+    41 -> 42 [ label = "" ]
+    38 -> 42 [ label = "" ]
+    39 -> 40 [ label = "control" ]
+    42 -> 35 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    assert_eq!(wyre!{ bump(1, 2) }, 7);
+    assert_eq!(wyre!{ shift(Point{x: 1, y: 2}, 3) }, 6);
+    assert_eq!(wyre!{ set(vec![1, 2], 0) }, 11);
+    assert_eq!(wyre!{ pick(true) }, 5);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
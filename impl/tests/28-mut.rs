@@ -0,0 +1,161 @@
+// Check that `&mut` parameters and receivers are recorded again on exit,
+// and that the caller's variables they borrowed get edges from there, from
+// the last call that changed them, not one that only read them.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[derive(Debug)]
+struct Tally {
+    total: u64,
+}
+
+#[wye]
+impl Tally {
+    fn add(&mut self, by: u64) {
+        self.total += by;
+    }
+}
+
+#[wye]
+fn append(v: &mut Vec<u64>, x: u64) {
+    v.push(x);
+}
+
+#[derive(Debug)]
+struct Simple;
+
+#[wye]
+fn push_len(v: &mut Vec<u64>) -> usize {
+    v.push(7);
+    v.len()
+}
+
+#[wye]
+fn empty(v: &mut Vec<u64>) -> Simple {
+    v.clear();
+    Simple
+}
+
+#[wye]
+fn first_mut(v: &mut Vec<u64>) -> &mut u64 {
+    &mut v[0]
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "v = [1, 2]" ]
+    1 [ label = "append(&mut v, 3) = ()" ]
+    2 [ label = "&mut v = [1, 2]" ]
+    3 [ label = "3" ]
+    4 [ label = "v = [1, 2]" ]
+    5 [ label = "x = 3" ]
+    6 [ label = "v.push(x) = ()" ]
+    7 [ label = "v = [1, 2]" ]
+    8 [ label = "v = [1, 2, 3]" ]
+    9 [ label = "tally = Tally { total: 0 }" ]
+    10 [ label = "0" ]
+    11 [ label = "tally.add(4) = ()" ]
+    12 [ label = "tally = Tally { total: 0 }" ]
+    13 [ label = "4" ]
+    14 [ label = "self = Tally { total: 0 }" ]
+    15 [ label = "by = 4" ]
+    16 [ label = "self = Tally { total: 4 }" ]
+    17 [ label = "self = Tally { total: 4 }" ]
+    18 [ label = "first = 1" ]
+    19 [ label = "first_mut(&mut v) = 1" ]
+    20 [ label = "&mut v = [1, 2, 3]" ]
+    21 [ label = "v = [1, 2, 3]" ]
+    22 [ label = "1" ]
+    23 [ label = "5" ]
+    24 [ label = "first = 6" ]
+    25 [ label = "w = []" ]
+    26 [ label = "push_len(&mut w) = 1" ]
+    27 [ label = "&mut w = []" ]
+    28 [ label = "v = []" ]
+    29 [ label = "v.push(7) = ()" ]
+    30 [ label = "v = []" ]
+    31 [ label = "7" ]
+    32 [ label = "v.len() = 1" ]
+    33 [ label = "v = [7]" ]
+    34 [ label = "v = [7]" ]
+    35 [ label = "empty(&mut w) = Simple" ]
+    36 [ label = "&mut w = [7]" ]
+    37 [ label = "v = [7]" ]
+    38 [ label = "v.clear() = ()" ]
+    39 [ label = "v = [7]" ]
+    40 [ label = "v = []" ]
+    41 [ label = "+ = 10" ]
+    42 [ label = "4" ]
+    43 [ label = "6" ]
+    44 [ label = "0" ]
+    0 -> 2 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
+    4 -> 7 [ label = "" ]
+    4 -> 6 [ label = "" ]
+    5 -> 6 [ label = "" ]
+    7 -> 6 [ label = "" ]
+    6 -> 8 [ label = "" ]
+    6 -> 1 [ label = "" ]
+    0 -> 1 [ label = "" ]
+    10 -> 9 [ label = "" ]
+    9 -> 12 [ label = "" ]
+    12 -> 14 [ label = "" ]
+    13 -> 15 [ label = "" ]
+    15 -> 16 [ label = "" ]
+    14 -> 16 [ label = "" ]
+    16 -> 17 [ label = "" ]
+    16 -> 11 [ label = "" ]
+    9 -> 11 [ label = "" ]
+    8 -> 20 [ label = "" ]
+    20 -> 21 [ label = "" ]
+    21 -> 22 [ label = "" ]
+    22 -> 19 [ label = "" ]
+    8 -> 19 [ label = "" ]
+    19 -> 18 [ label = "" ]
+    23 -> 24 [ label = "" ]
+    18 -> 24 [ label = "" ]
+    25 -> 27 [ label = "" ]
+    27 -> 28 [ label = "" ]
+    28 -> 30 [ label = "" ]
+    28 -> 29 [ label = "" ]
+    30 -> 29 [ label = "" ]
+    31 -> 29 [ label = "" ]
+    29 -> 33 [ label = "" ]
+    29 -> 32 [ label = "" ]
+    33 -> 32 [ label = "" ]
+    29 -> 34 [ label = "" ]
+    32 -> 26 [ label = "" ]
+    25 -> 26 [ label = "" ]
+    34 -> 36 [ label = "" ]
+    36 -> 37 [ label = "" ]
+    37 -> 39 [ label = "" ]
+    37 -> 38 [ label = "" ]
+    39 -> 38 [ label = "" ]
+    38 -> 40 [ label = "" ]
+    38 -> 35 [ label = "" ]
+    34 -> 35 [ label = "" ]
+    17 -> 42 [ label = "" ]
+    44 -> 43 [ label = "" ]
+    8 -> 43 [ label = "" ]
+    42 -> 41 [ label = "" ]
+    43 -> 41 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    assert_eq!(wyre!{
+        let mut v = vec![1, 2];
+        append(&mut v, 3);
+        let mut tally = Tally{total: 0};
+        tally.add(4);
+        let first = first_mut(&mut v);
+        *first += 5;
+        let mut w = vec![];
+        push_len(&mut w);
+        empty(&mut w);
+        tally.total + v[0]
+    }, 10);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+}
@@ -0,0 +1,96 @@
+// Check that closures are instrumented like functions, that each of their
+// invocations gets a frame of its own, and that their results are linked to
+// the calls they are passed to.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[wye]
+fn doubled(xs: &[u64]) -> Vec<u64> {
+    let ys: Vec<u64> = xs.iter().map(|x| x * 2).collect();
+    ys
+}
+
+#[wye]
+fn offset(base: u64) -> u64 {
+    let add = |n: u64| n + base;
+    add(1) + add(2)
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "doubled(&xs) = [2, 4]" ]
+    1 [ label = "&xs = [1, 2]" ]
+    2 [ label = "xs = [1, 2]" ]
+    3 [ label = "ys = [2, 4]" ]
+    4 [ label = "xs.iter().map(|x| x * 2).collect() = [2, 4]" ]
+    5 [ label = "xs.iter().map(|x| x * 2) = Map { iter: Iter([1, 2]) }" ]
+    6 [ label = "xs.iter() = Iter([1, 2])" ]
+    7 [ label = "xs = [1, 2]" ]
+    8 [ label = "x = 1" ]
+    9 [ label = "* = 2" ]
+    10 [ label = "2" ]
+    11 [ label = "x = 2" ]
+    12 [ label = "* = 4" ]
+    13 [ label = "2" ]
+    14 [ label = "offset(10) = 23" ]
+    15 [ label = "10" ]
+    16 [ label = "base = 10" ]
+    17 [ label = "add = <{CRATE}::offset::{{closure}}; {CLOSURE_SIZE} bytes>" ]
+    18 [ label = "+ = 23" ]
+    19 [ label = "add(1) = 11" ]
+    20 [ label = "1" ]
+    21 [ label = "n = 1" ]
+    22 [ label = "+ = 11" ]
+    23 [ label = "add(2) = 12" ]
+    24 [ label = "2" ]
+    25 [ label = "n = 2" ]
+    26 [ label = "+ = 12" ]
+    1 -> 2 [ label = "" ]
+    2 -> 7 [ label = "" ]
+    2 -> 6 [ label = "" ]
+    7 -> 6 [ label = "" ]
+    2 -> 5 [ label = "" ]
+    6 -> 5 [ label = "" ]
+    10 -> 9 [ label = "" ]
+    8 -> 9 [ label = "" ]
+    9 -> 5 [ label = "" ]
+    13 -> 12 [ label = "" ]
+    11 -> 12 [ label = "" ]
+    12 -> 5 [ label = "" ]
+    12 -> 4 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    4 -> 3 [ label = "" ]
+    3 -> 0 [ label = "" ]
+    15 -> 16 [ label = "" ]
+    16 -> 17 [ label = "" ]
+    20 -> 21 [ label = "" ]
+    21 -> 22 [ label = "" ]
+    16 -> 22 [ label = "" ]
+    22 -> 19 [ label = "" ]
+    17 -> 19 [ label = "" ]
+    24 -> 25 [ label = "" ]
+    25 -> 26 [ label = "" ]
+    16 -> 26 [ label = "" ]
+    26 -> 23 [ label = "" ]
+    17 -> 23 [ label = "" ]
+    19 -> 18 [ label = "" ]
+    23 -> 18 [ label = "" ]
+    18 -> 14 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    let xs = vec![1, 2];
+    assert_eq!(wyre!{ doubled(&xs) }, [2, 4]);
+    assert_eq!(wyre!{ offset(10) }, 23);
+    eprintln!("{}", get_wye());
+    // add captures two references, to base and to the frame it is in:
+    let expected = EXPECTED_GRAPH.replace("{CRATE}", env!("CARGO_CRATE_NAME"))
+        .replace("{CLOSURE_SIZE}", &(2 * std::mem::size_of::<&u64>()).to_string());
+    assert_eq!(format!("{}", get_wye()).trim(), expected.trim());
+    let functions = get_wye().trace().nodes.into_iter()
+        .filter(|node| node.var.as_deref() == Some("n"))
+        .map(|node| node.function.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(functions, ["offset::{closure}", "offset::{closure}"]);
+}
@@ -0,0 +1,181 @@
+// Check that the values leaving functions early, with `return` or a `?` that
+// short-circuits, and leaving loops, with `break`, are linked to what they
+// are the values of, labeled with how they left.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[wye]
+fn clamp(x: u64, max: u64) -> u64 {
+    if x > max {
+        return max;
+    }
+    x
+}
+
+#[wye]
+fn add(a: &str, b: &str) -> Option<u64> {
+    let a = a.parse::<u64>().ok()?;
+    let b = b.parse::<u64>().ok()?;
+    Some(a + b)
+}
+
+#[wye]
+fn square_above(limit: u64) -> u64 {
+    let mut n = 0;
+    let square = 'search: loop {
+        loop {
+            n += 1;
+            if n * n > limit {
+                break 'search n * n;
+            }
+        }
+    };
+    square
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "clamp(7, 5) = 5" ]
+    1 [ label = "7" ]
+    2 [ label = "5" ]
+    3 [ label = "x = 7" ]
+    4 [ label = "max = 5" ]
+    5 [ label = "> = true" ]
+    6 [ label = "add(\"1\", \"2\") = Some(3)" ]
+    7 [ label = "\"1\"" ]
+    8 [ label = "\"2\"" ]
+    9 [ label = "a = \"1\"" ]
+    10 [ label = "b = \"2\"" ]
+    11 [ label = "a = 1" ]
+    12 [ label = "a.parse::<u64>().ok() = Some(1)" ]
+    13 [ label = "a.parse::<u64>() = Ok(1)" ]
+    14 [ label = "a = \"1\"" ]
+    15 [ label = "1" ]
+    16 [ label = "b = 2" ]
+    17 [ label = "b.parse::<u64>().ok() = Some(2)" ]
+    18 [ label = "b.parse::<u64>() = Ok(2)" ]
+    19 [ label = "b = \"2\"" ]
+    20 [ label = "2" ]
+    21 [ label = "Some(a + b) = Some(3)" ]
+    22 [ label = "+ = 3" ]
+    23 [ label = "add(\"1\", \"x\") = None" ]
+    24 [ label = "\"1\"" ]
+    25 [ label = "\"x\"" ]
+    26 [ label = "a = \"1\"" ]
+    27 [ label = "b = \"x\"" ]
+    28 [ label = "a = 1" ]
+    29 [ label = "a.parse::<u64>().ok() = Some(1)" ]
+    30 [ label = "a.parse::<u64>() = Ok(1)" ]
+    31 [ label = "a = \"1\"" ]
+    32 [ label = "1" ]
+    33 [ label = "" ]
+    34 [ label = "b.parse::<u64>().ok() = None" ]
+    35 [ label = "b.parse::<u64>() = Err(ParseIntError { kind: InvalidDigit })" ]
+    36 [ label = "b = \"x\"" ]
+    37 [ label = "square_above(10) = 16" ]
+    38 [ label = "10" ]
+    39 [ label = "limit = 10" ]
+    40 [ label = "n = 0" ]
+    41 [ label = "square = 16" ]
+    42 [ label = "1" ]
+    43 [ label = "n = 1" ]
+    44 [ label = "> = false" ]
+    45 [ label = "* = 1" ]
+    46 [ label = "1" ]
+    47 [ label = "n = 2" ]
+    48 [ label = "> = false" ]
+    49 [ label = "* = 4" ]
+    50 [ label = "1" ]
+    51 [ label = "n = 3" ]
+    52 [ label = "> = false" ]
+    53 [ label = "* = 9" ]
+    54 [ label = "1" ]
+    55 [ label = "n = 4" ]
+    56 [ label = "> = true" ]
+    57 [ label = "* = 16" ]
+    58 [ label = "* = 16" ]
+    1 -> 3 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
+    4 -> 5 [ label = "" ]
+    4 -> 0 [ label = "return" ]
+    7 -> 9 [ label = "" ]
+    8 -> 10 [ label = "" ]
+    9 -> 14 [ label = "" ]
+    9 -> 13 [ label = "" ]
+    14 -> 13 [ label = "" ]
+    9 -> 12 [ label = "" ]
+    13 -> 12 [ label = "" ]
+    12 -> 15 [ label = "" ]
+    15 -> 11 [ label = "" ]
+    10 -> 19 [ label = "" ]
+    10 -> 18 [ label = "" ]
+    19 -> 18 [ label = "" ]
+    10 -> 17 [ label = "" ]
+    18 -> 17 [ label = "" ]
+    17 -> 20 [ label = "" ]
+    20 -> 16 [ label = "" ]
+    11 -> 22 [ label = "" ]
+    16 -> 22 [ label = "" ]
+    11 -> 21 [ label = "" ]
+    16 -> 21 [ label = "" ]
+    22 -> 21 [ label = "" ]
+    21 -> 6 [ label = "" ]
+    24 -> 26 [ label = "" ]
+    25 -> 27 [ label = "" ]
+    26 -> 31 [ label = "" ]
+    26 -> 30 [ label = "" ]
+    31 -> 30 [ label = "" ]
+    26 -> 29 [ label = "" ]
+    30 -> 29 [ label = "" ]
+    29 -> 32 [ label = "" ]
+    32 -> 28 [ label = "" ]
+    27 -> 36 [ label = "" ]
+    27 -> 35 [ label = "" ]
+    36 -> 35 [ label = "" ]
+    27 -> 34 [ label = "" ]
+    35 -> 34 [ label = "" ]
+    34 -> 23 [ label = "?" ]
+    38 -> 39 [ label = "" ]
+    42 -> 43 [ label = "" ]
+    40 -> 43 [ label = "" ]
+    43 -> 45 [ label = "" ]
+    45 -> 44 [ label = "" ]
+    39 -> 44 [ label = "" ]
+    46 -> 47 [ label = "" ]
+    43 -> 47 [ label = "" ]
+    47 -> 49 [ label = "" ]
+    49 -> 48 [ label = "" ]
+    39 -> 48 [ label = "" ]
+    50 -> 51 [ label = "" ]
+    47 -> 51 [ label = "" ]
+    51 -> 53 [ label = "" ]
+    53 -> 52 [ label = "" ]
+    39 -> 52 [ label = "" ]
+    54 -> 55 [ label = "" ]
+    51 -> 55 [ label = "" ]
+    55 -> 57 [ label = "" ]
+    57 -> 56 [ label = "" ]
+    39 -> 56 [ label = "" ]
+    56 -> 58 [ label = "control" ]
+    55 -> 58 [ label = "" ]
+    58 -> 41 [ label = "break" ]
+    55 -> 41 [ label = "" ]
+    39 -> 41 [ label = "" ]
+    41 -> 37 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    assert_eq!(wyre!{ clamp(7, 5) }, 5);
+    assert_eq!(wyre!{ add("1", "2") }, Some(3));
+    assert_eq!(wyre!{ add("1", "x") }, None);
+    assert_eq!(wyre!{ square_above(10) }, 16);
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+    let labels = get_wye().trace().edges.into_iter()
+        .map(|edge| edge.label)
+        .filter(|label| ![trace::CONTROL, ""].contains(&label.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(labels, [trace::RETURN, trace::TRY, trace::BREAK]);
+}
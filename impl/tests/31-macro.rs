@@ -0,0 +1,106 @@
+// Check that the arguments of std macros are instrumented, leaving their
+// format strings and patterns as they are, and that other macros are
+// recorded as opaque nodes with edges from the variables in their tokens.
+use std::fmt::Write;
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+macro_rules! twice {
+    ($x:expr) => { $x * 2 };
+}
+
+#[wye]
+fn fill(x: u64, n: usize) -> Vec<u64> {
+    let zeros = vec![0; n];
+    assert_eq!(zeros.len(), n, "{} zeros", n);
+    let doubled = twice!(x);
+    let filled = vec![x, doubled];
+    assert!(filled.len() >= n, "{} < {n}", filled.len());
+    filled
+}
+
+#[wye]
+fn describe(x: Option<u64>) -> String {
+    let mut s = String::new();
+    write!(s, "{:?}", x).unwrap();
+    let big = matches!(x, Some(v) if v > 1);
+    dbg!(big);
+    format!("{s} {big}")
+}
+
+const EXPECTED_GRAPH: &str = r#"
+digraph {
+    0 [ label = "fill(3, 2) = [3, 6]" ]
+    1 [ label = "3" ]
+    2 [ label = "2" ]
+    3 [ label = "x = 3" ]
+    4 [ label = "n = 2" ]
+    5 [ label = "zeros = [0, 0]" ]
+    6 [ label = "assert_eq!(zeros.len(), n, \"{} zeros\", n) = ()" ]
+    7 [ label = "zeros.len() = 2" ]
+    8 [ label = "zeros = [0, 0]" ]
+    9 [ label = "doubled = 6" ]
+    10 [ label = "twice!(x) = <skipped>" ]
+    11 [ label = "filled = [3, 6]" ]
+    12 [ label = "assert!(filled.len() >= n, \"{} < {n}\", filled.len()) = ()" ]
+    13 [ label = ">= = true" ]
+    14 [ label = "filled.len() = 2" ]
+    15 [ label = "filled = [3, 6]" ]
+    16 [ label = "describe(Some(2)) = \"Some(2) true\"" ]
+    17 [ label = "Some(2) = Some(2)" ]
+    18 [ label = "2" ]
+    19 [ label = "x = Some(2)" ]
+    20 [ label = "s = \"\"" ]
+    21 [ label = "String::new() = \"\"" ]
+    22 [ label = "write!(s, \"{:?}\", x).unwrap() = ()" ]
+    23 [ label = "write!(s, \"{:?}\", x) = Ok(())" ]
+    24 [ label = "big = true" ]
+    25 [ label = "dbg!(big) = true" ]
+    26 [ label = "format!(\"{s} {big}\") = \"Some(2) true\"" ]
+    1 -> 3 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    4 -> 5 [ label = "" ]
+    5 -> 8 [ label = "" ]
+    5 -> 7 [ label = "" ]
+    8 -> 7 [ label = "" ]
+    7 -> 6 [ label = "" ]
+    4 -> 6 [ label = "" ]
+    3 -> 10 [ label = "" ]
+    10 -> 9 [ label = "" ]
+    3 -> 11 [ label = "" ]
+    9 -> 11 [ label = "" ]
+    11 -> 15 [ label = "" ]
+    11 -> 14 [ label = "" ]
+    15 -> 14 [ label = "" ]
+    14 -> 13 [ label = "" ]
+    4 -> 13 [ label = "" ]
+    13 -> 12 [ label = "" ]
+    4 -> 12 [ label = "" ]
+    11 -> 0 [ label = "" ]
+    18 -> 17 [ label = "" ]
+    17 -> 19 [ label = "" ]
+    21 -> 20 [ label = "" ]
+    20 -> 23 [ label = "" ]
+    19 -> 23 [ label = "" ]
+    20 -> 22 [ label = "" ]
+    19 -> 22 [ label = "" ]
+    23 -> 22 [ label = "" ]
+    19 -> 24 [ label = "" ]
+    24 -> 25 [ label = "" ]
+    20 -> 26 [ label = "" ]
+    24 -> 26 [ label = "" ]
+    26 -> 16 [ label = "" ]
+}
+"#;
+
+pub fn main() {
+    assert_eq!(wyre!{ fill(3, 2) }, [3, 6]);
+    assert_eq!(wyre!{ describe(Some(2)) }, "Some(2) true");
+    eprintln!("{}", get_wye());
+    assert_eq!(format!("{}", get_wye()).trim(), EXPECTED_GRAPH.trim());
+    let opaque = get_wye().trace().nodes.into_iter()
+        .filter(|node| node.val == "<skipped>")
+        .map(|node| node.var.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(opaque, ["twice!(x)"]);
+}
@@ -0,0 +1,52 @@
+// Check that const and async fns in annotated modules and impls are left as
+// they are, whether or not the filters exclude them, rather than rejected.
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[wye(exclude: "app::k")]
+mod app {
+    pub const fn k() -> u64 {
+        2
+    }
+
+    pub const fn double(n: u64) -> u64 {
+        n * 2
+    }
+
+    pub async fn later(n: u64) -> u64 {
+        n
+    }
+
+    pub fn run(n: u64) -> u64 {
+        n + double(k())
+    }
+}
+
+pub struct Meter(u64);
+
+#[wye]
+impl Meter {
+    pub const fn new(start: u64) -> Self {
+        Meter(start)
+    }
+
+    pub fn read(&self) -> u64 {
+        self.0
+    }
+}
+
+const FOUR: u64 = app::double(app::k());
+
+pub fn main() {
+    let _later = app::later(1);
+    let meter = Meter::new(1);
+    assert_eq!(wyre!{
+        app::run(meter.read())
+    }, 1 + FOUR);
+    let mut functions = get_wye().trace().nodes.into_iter()
+        .filter_map(|node| node.function)
+        .collect::<Vec<_>>();
+    functions.sort();
+    functions.dedup();
+    assert_eq!(functions, ["Meter::read", "app::run"]);
+}
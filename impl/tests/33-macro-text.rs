@@ -0,0 +1,35 @@
+// Check that assert! and dbg! print their arguments as written rather than
+// as instrumented.
+use std::process::Command;
+use wye::*;
+use pretty_assertions::{assert_eq};
+
+#[wye]
+fn check(n: u64) -> u64 {
+    assert!(n + 1 < 2);
+    n
+}
+
+#[wye]
+fn show(n: u64) -> (u64, u64) {
+    let m = dbg!(n + 1);
+    dbg!(n * 2, m)
+}
+
+pub fn main() {
+    if std::env::var_os("WYE_SHOW").is_some() {
+        show(1);
+        return;
+    }
+
+    std::panic::set_hook(Box::new(|_| {}));
+    let panic = std::panic::catch_unwind(|| check(1)).unwrap_err();
+    assert_eq!(panic.downcast_ref::<String>().map(String::as_str), Some("assertion failed: n + 1 < 2"));
+
+    let output = Command::new(std::env::current_exe().unwrap()).env("WYE_SHOW", "1").output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), format!(
+        "[{file}:15:13] n + 1 = 2\n[{file}:16:5] n * 2 = 2\n[{file}:16:5] m = 2\n",
+        file = file!(),
+    ));
+}
@@ -0,0 +1,12 @@
+// Check that async fns and blocks are rejected.
+use wye::*;
+
+#[wye]
+async fn inc(a: u64) -> u64 { a + 1 }
+
+#[wye]
+fn later(a: u64) -> impl std::future::Future<Output = u64> {
+    async move { a + 1 }
+}
+
+pub fn main() {}
@@ -0,0 +1,7 @@
+// Check that const fns, which run at compile time, are rejected.
+use wye::*;
+
+#[wye]
+const fn inc(a: u64) -> u64 { a + 1 }
+
+pub fn main() {}
@@ -0,0 +1,10 @@
+// Check that items with nothing to instrument are rejected.
+use wye::*;
+
+#[wye]
+struct Point { x: u64, y: u64 }
+
+#[wye]
+const ORIGIN: u64 = 0;
+
+pub fn main() {}
@@ -0,0 +1,12 @@
+// Check that formatters for names that are never recorded are rejected.
+use wye::*;
+
+#[wye(a: format!("{a:?}"), c: format!("{c:?}"))]
+fn add(a: u64, b: u64) -> u64 { a + b }
+
+pub fn main() {
+    let x = 1;
+    wyre!{(y: format!("{y}"))
+        add(x, 2)
+    };
+}
@@ -1,17 +1,43 @@
 #[test]
 fn test() {
+    // cargo does not track WYE_MANIFEST_DIR, so expansions cached by an
+    // earlier run do not write their manifests again; the directory must
+    // outlive the run that filled it:
+    let manifest_dir = concat!(env!("CARGO_TARGET_TMPDIR"), "/wye-sites");
+    std::env::set_var("WYE_MANIFEST_DIR", manifest_dir);
     let t = trybuild::TestCases::new();
     t.pass("tests/01-nil.rs");
     t.pass("tests/02-print.rs");
     t.pass("tests/03-add-add.rs");
     t.pass("tests/04-let.rs");
     t.pass("tests/05-format.rs");
-    // t.pass("tests/06-slice.rs");
-    // t.pass("tests/07-debug.rs");
-    // t.pass("tests/08-impl-trait-arg.rs");
-    // t.pass("tests/09-generic-fn.rs");
-    // t.pass("tests/10-where-clause.rs");
-    // t.pass("tests/11-multiple-generics.rs");
-    // t.pass("tests/12-destructuring-let.rs");
+    t.pass("tests/06-slice.rs");
+    t.pass("tests/07-debug.rs");
+    t.pass("tests/08-impl-trait-arg.rs");
+    t.pass("tests/09-generic-fn.rs");
+    t.pass("tests/10-where-clause.rs");
+    t.pass("tests/11-multiple-generics.rs");
+    t.pass("tests/12-destructuring-let.rs");
     t.pass("tests/13-none.rs");
+    t.pass("tests/14-location.rs");
+    t.pass("tests/15-site-id.rs");
+    t.pass("tests/16-manifest.rs");
+    t.pass("tests/17-capture.rs");
+    t.pass("tests/18-skip.rs");
+    t.pass("tests/19-wye-value.rs");
+    t.pass("tests/20-structured.rs");
+    t.pass("tests/21-policy.rs");
+    t.pass("tests/22-method-call.rs");
+    t.pass("tests/23-impl.rs");
+    t.pass("tests/24-mod.rs");
+    t.pass("tests/25-branch.rs");
+    t.pass("tests/26-loop.rs");
+    t.pass("tests/27-assign.rs");
+    t.pass("tests/28-mut.rs");
+    t.pass("tests/29-closure.rs");
+    t.pass("tests/30-exit.rs");
+    t.pass("tests/31-macro.rs");
+    t.pass("tests/32-implicit.rs");
+    t.pass("tests/33-macro-text.rs");
+    t.compile_fail("tests/fail/*.rs");
 }
@@ -0,0 +1,130 @@
+//! # Overview
+//!
+//! `wye` inspects traces saved by [wye::Logger::dump].
+//!
+//! ```text
+//! wye render [--format FORMAT] [TRACE]
+//! wye why [--format FORMAT] SELECTOR [TRACE]
+//! wye impact [--format FORMAT] SELECTOR [TRACE]
+//! wye select [--format FORMAT] SELECTOR [TRACE]
+//! wye diff A B
+//! wye stats [TRACE]
+//! wye coverage MANIFEST [TRACE]
+//! ```
+//!
+//! FORMAT is one of `dot`, `mermaid`, `html`, or `text` (the default).
+//!
+//! TRACE is a path to a saved trace, or `-` for stdin (the default).
+//!
+//! SELECTOR is a node id, a variable name, `function::variable`, or a query
+//! on structured values like `.status == 500`; see [wye::trace].
+//!
+//! `select` prints just the matching nodes and the edges between them, and
+//! exits with status 1 if there are none.
+//!
+//! MANIFEST is a site manifest file or directory; see [wye::manifest].
+
+use std::{fs::File, io::{self, Read}, process::ExitCode};
+
+use wye::{manifest::Manifest, render::{self, Format}, trace::{Direction, Trace}};
+
+const USAGE: &str = "usage:
+    wye render [--format FORMAT] [TRACE]
+    wye why [--format FORMAT] SELECTOR [TRACE]
+    wye impact [--format FORMAT] SELECTOR [TRACE]
+    wye select [--format FORMAT] SELECTOR [TRACE]
+    wye diff A B
+    wye stats [TRACE]
+    wye coverage MANIFEST [TRACE]";
+
+fn load(path: Option<&str>) -> Result<Trace, String> {
+    let result = match path {
+        None | Some("-") => {
+            let mut buf = vec![];
+            io::stdin().read_to_end(&mut buf).map_err(|e| format!("reading stdin: {e}"))?;
+            Trace::load(&buf[..])
+        },
+        Some(path) => {
+            let file = File::open(path).map_err(|e| format!("{path}: {e}"))?;
+            Trace::load(io::BufReader::new(file))
+        },
+    };
+    result.map_err(|e| format!("{}: {e}", path.unwrap_or("-")))
+}
+
+/// Splits `--format FORMAT` out of `args`, leaving the positional arguments.
+fn parse_format(args: &[String]) -> Result<(Format, Vec<&str>), String> {
+    let mut format = Format::default();
+    let mut positional = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--format" || arg == "-f" {
+            let value = iter.next().ok_or("missing value for --format")?;
+            format = value.parse()?;
+        } else if let Some(value) = arg.strip_prefix("--format=") {
+            format = value.parse()?;
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    Ok((format, positional))
+}
+
+fn run(args: &[String]) -> Result<ExitCode, String> {
+    let (command, rest) = args.split_first().ok_or(USAGE)?;
+    let (format, positional) = parse_format(rest)?;
+    match (command.as_str(), positional.as_slice()) {
+        ("render", path) if path.len() <= 1 => {
+            let trace = load(path.first().copied())?;
+            print!("{}", render::render(&trace, format));
+        },
+        (command @ ("why" | "impact"), [selector, path @ ..]) if path.len() <= 1 => {
+            let trace = load(path.first().copied())?;
+            let roots = trace.select(selector);
+            if roots.is_empty() {
+                return Err(format!("no nodes match selector: {selector}"));
+            }
+            let direction = if command == "why" { Direction::Why } else { Direction::Impact };
+            print!("{}", render::render(&trace.slice(&roots, direction), format));
+        },
+        ("select", [selector, path @ ..]) if path.len() <= 1 => {
+            let trace = load(path.first().copied())?;
+            let selected = trace.select(selector);
+            if selected.is_empty() {
+                return Ok(ExitCode::from(1));
+            }
+            print!("{}", render::render(&trace.subset(&selected.into_iter().collect()), format));
+        },
+        ("diff", [a, b]) => {
+            let changes = load(Some(a))?.diff(&load(Some(b))?);
+            for change in &changes {
+                println!("{change}");
+            }
+            if !changes.is_empty() {
+                return Ok(ExitCode::from(1));
+            }
+        },
+        ("stats", path) if path.len() <= 1 => {
+            let trace = load(path.first().copied())?;
+            print!("{}", trace.stats());
+        },
+        ("coverage", [manifest, path @ ..]) if path.len() <= 1 => {
+            let manifest = Manifest::load_path(manifest).map_err(|e| format!("{manifest}: {e}"))?;
+            let trace = load(path.first().copied())?;
+            print!("{}", manifest.coverage(&trace));
+        },
+        _ => return Err(USAGE.into()),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("wye: {err}");
+            ExitCode::from(2)
+        },
+    }
+}
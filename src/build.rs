@@ -0,0 +1,90 @@
+//! # Overview
+//!
+//! [instrument] lets a build script instrument a whole module tree, rather
+//! than one inline module at a time, by rewriting its source into a single
+//! inline module annotated with [wye](crate::wye), which the crate then
+//! includes in place of the module's `mod` declaration.
+//!
+//! For example, with `wye` also listed under `[build-dependencies]`, a
+//! `build.rs` like
+//!
+//! ```rust,no_run
+//! fn main() -> std::io::Result<()> {
+//!     wye::build::instrument("src/app.rs", r#"exclude: "app::tests::*""#)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! lets `src/lib.rs` replace `mod app;` by
+//!
+//! ```rust,ignore
+//! include!(concat!(env!("OUT_DIR"), "/wye/app.rs"));
+//! ```
+//!
+//! To instrument a whole crate, move its items from `src/lib.rs` into
+//! `src/app.rs` and re-export them with `pub use app::*;`.
+//!
+//! Nested modules are found by the usual rules, in `app/net.rs` or
+//! `app/net/mod.rs`, from `mod` declarations on lines of their own;
+//! `#[path]` attributes are not followed.
+
+use std::{env, fs, io, path::{Path, PathBuf}};
+
+/// Writes the module tree rooted at `root` to `$OUT_DIR/wye/<name>.rs`, as
+/// an inline `pub mod <name>` annotated with `#[wye(<args>)]`, and returns
+/// the path written; see above.
+pub fn instrument(root: impl AsRef<Path>, args: &str) -> io::Result<PathBuf> {
+    let root = root.as_ref();
+    let name = root.file_stem()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("bad module path: {}", root.display())))?;
+    let dir = env::var_os("OUT_DIR")
+        .map(|dir| PathBuf::from(dir).join("wye"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "OUT_DIR is not set"))?;
+    let source = format!("#[::wye::wye({args})]\npub mod {name} {{\n{}}}\n", inline(root)?);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{name}.rs"));
+    fs::write(&path, source)?;
+    Ok(path)
+}
+
+/// Returns the source of the module file `path` with its `mod name;`
+/// declarations replaced by inline modules holding their files' source,
+/// recursively, and asks Cargo to rerun the build script when any of
+/// those files change.
+pub fn inline(path: &Path) -> io::Result<String> {
+    println!("cargo:rerun-if-changed={}", path.display());
+    let source = fs::read_to_string(path)?;
+    // like rustc, look for the children of foo.rs in foo/ and those of
+    // mod.rs in its own directory:
+    let dir = match path.file_name() {
+        Some(name) if name == "mod.rs" => path.parent().unwrap_or(Path::new("")).to_path_buf(),
+        _ => path.with_extension(""),
+    };
+    let mut inlined = String::new();
+    for line in source.lines() {
+        match mod_decl(line) {
+            Some((head, name)) => {
+                let file = dir.join(format!("{name}.rs"));
+                let file = if file.exists() { file } else { dir.join(name).join("mod.rs") };
+                inlined.push_str(&format!("{head} {{\n{}}}\n", inline(&file)?));
+            },
+            None => {
+                inlined.push_str(line);
+                inlined.push('\n');
+            },
+        }
+    }
+    Ok(inlined)
+}
+
+/// Splits a line like `pub(crate) mod net;` into `pub(crate) mod net` and
+/// `net`, if it declares an out-of-line module.
+fn mod_decl(line: &str) -> Option<(&str, &str)> {
+    let head = line.trim_end().strip_suffix(';')?;
+    let (vis, name) = head.rsplit_once("mod ")?;
+    let vis = vis.trim();
+    let is_vis = vis.is_empty() || vis == "pub" || vis.starts_with("pub(") && vis.ends_with(')');
+    let is_ident = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (is_vis && is_ident).then_some((head, name))
+}
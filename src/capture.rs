@@ -0,0 +1,139 @@
+//! # Overview
+//!
+//! [Capture] formats the values recorded by the code that [wye](crate::wye)
+//! and [wyre](crate::wyre) generate, using the first of these that the
+//! value's type supports:
+//!
+//! 1. [WyeValue],
+//! 2. [std::fmt::Debug],
+//! 3. [std::fmt::Display],
+//! 4. [Describe], for types that implement neither,
+//! 5. the type's name and size, like `<my_crate::Conn; 48 bytes>`.
+//!
+//! The choice is made at compile time, with no annotations needed, by
+//! "autoref specialization": the generated code calls
+//!
+//! ```rust
+//! use wye::capture::*;
+//!
+//! struct Opaque;
+//!
+//! assert_eq!((&&&&&Capture(&5)).capture(), "5");
+//! assert!((&&&&&Capture(&Opaque)).capture().ends_with("Opaque; 0 bytes>"));
+//! ```
+//!
+//! and method resolution picks the impl needing the fewest auto-derefs
+//! whose bounds hold, which is the one earliest in the list above.
+//!
+//! Since this happens where the value is recorded, values of generic types
+//! are formatted according to the bounds in scope there rather than
+//! according to the type they are eventually instantiated with.
+//!
+//! # Structured Values
+//!
+//! With the `serde` feature enabled, values whose types implement
+//! `serde::Serialize` are also recorded in structured form, as the
+//! [Value::data] of their nodes, by the same trick:
+//!
+//! ```rust
+//! use wye::capture::*;
+//!
+//! let data = (&&Capture(&[1, 2])).structured();
+//! # #[cfg(feature = "serde")]
+//! assert_eq!(data, Some(serde_json::json!([1, 2])));
+//! # #[cfg(not(feature = "serde"))]
+//! assert_eq!(data, None);
+//! ```
+
+use std::fmt::{Debug, Display};
+
+use crate::value::{Data, Value, WyeValue};
+
+/// A custom description for types that implement neither [Debug] nor
+/// [Display].
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+/// Wraps a reference to a value to be formatted; see above.
+pub struct Capture<'a, T: ?Sized>(pub &'a T);
+
+/// Combines the results of [Capture]'s `capture` and `structured` methods.
+pub fn value(label: impl Into<Value>, data: Option<Data>) -> Value {
+    let mut value = label.into();
+    if data.is_some() {
+        value.data = data;
+    }
+    value
+}
+
+pub trait ViaWyeValue {
+    fn capture(&self) -> Value;
+}
+
+impl<T: WyeValue + ?Sized> ViaWyeValue for &&&&Capture<'_, T> {
+    fn capture(&self) -> Value {
+        Value::new(self.0)
+    }
+}
+
+pub trait ViaDebug {
+    fn capture(&self) -> String;
+}
+
+impl<T: Debug + ?Sized> ViaDebug for &&&Capture<'_, T> {
+    fn capture(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+pub trait ViaDisplay {
+    fn capture(&self) -> String;
+}
+
+impl<T: Display + ?Sized> ViaDisplay for &&Capture<'_, T> {
+    fn capture(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+pub trait ViaDescribe {
+    fn capture(&self) -> String;
+}
+
+impl<T: Describe + ?Sized> ViaDescribe for &Capture<'_, T> {
+    fn capture(&self) -> String {
+        self.0.describe()
+    }
+}
+
+pub trait ViaTypeName {
+    fn capture(&self) -> String;
+}
+
+impl<T: ?Sized> ViaTypeName for Capture<'_, T> {
+    fn capture(&self) -> String {
+        format!("<{}; {} bytes>", std::any::type_name::<T>(), std::mem::size_of_val(self.0))
+    }
+}
+
+pub trait ViaSerialize {
+    fn structured(&self) -> Option<Data>;
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + ?Sized> ViaSerialize for &Capture<'_, T> {
+    fn structured(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self.0).ok()
+    }
+}
+
+pub trait ViaUnstructured {
+    fn structured(&self) -> Option<Data>;
+}
+
+impl<T: ?Sized> ViaUnstructured for Capture<'_, T> {
+    fn structured(&self) -> Option<Data> {
+        None
+    }
+}
@@ -0,0 +1,268 @@
+//! # Overview
+//!
+//! [Cursor] steps forwards and backwards in time through the events of a
+//! finished run, like a time-travel debugger.
+//!
+//! # Example
+//!
+//! ```rust
+//! use wye::{cursor::Cursor, trace::{Event, EventKind, Node, Trace}};
+//!
+//! let trace = Trace{
+//!     nodes: vec![Node{id: 0, var: Some("x".into()), val: "1".into(), ..Default::default()}],
+//!     events: vec![
+//!         Event{seq: 0, kind: EventKind::Declare{node: 0}},
+//!         Event{seq: 1, kind: EventKind::Define{node: 0}},
+//!     ],
+//!     ..Default::default()
+//! };
+//! let mut cursor = Cursor::new(&trace);
+//! assert!(cursor.variables().is_empty());
+//! cursor.step_forward();
+//! cursor.step_forward();
+//! assert_eq!(cursor.variables(), vec![("x", &trace.nodes[0])]);
+//! cursor.step_back();
+//! assert!(cursor.variables().is_empty());
+//! ```
+//!
+//! # Positions
+//!
+//! A cursor sits *between* events: at position `n`, the first `n` events
+//! have happened and [Cursor::event] is the most recent of them. Position 0
+//! is the start of the run and position `events.len()` is its end.
+//!
+//! # Frames
+//!
+//! Frames are delimited by [EventKind::PushFrame] and [EventKind::PopFrame],
+//! which the generated code emits around every instrumented call. Stepping
+//! over a push runs to the matching pop; stepping out runs to the pop that
+//! closes the current frame.
+//!
+//! Variables are additionally scoped by [EventKind::EnterFn] and
+//! [EventKind::ExitFn], so that a callee's parameters go out of scope when
+//! it returns even if its caller did not push a frame for the call.
+
+use std::collections::HashMap;
+
+use crate::trace::{Event, EventKind, Node, Trace};
+
+/// The variables in scope in a frame, with the nodes holding their latest
+/// values.
+type Scope<'t> = HashMap<&'t str, &'t Node>;
+
+pub struct Cursor<'t> {
+    trace: &'t Trace,
+    pos: usize,
+    nodes: HashMap<usize, &'t Node>,
+    depth: usize,
+    functions: Vec<&'t str>,
+    scopes: Vec<Scope<'t>>,
+    /// How to undo each event that has happened, to step back over it.
+    undo: Vec<Undo<'t>>,
+}
+
+/// What an event changed in the state of a [Cursor], that stepping back
+/// over it restores.
+enum Undo<'t> {
+    Nothing,
+    PushFrame,
+    EnterFn,
+    PopFrame{popped: bool, scope: Option<Scope<'t>>},
+    ExitFn{function: Option<&'t str>, scope: Option<Scope<'t>>},
+    Define{var: &'t str, previous: Option<&'t Node>},
+}
+
+impl<'t> Cursor<'t> {
+    pub fn new(trace: &'t Trace) -> Self {
+        Self{
+            trace,
+            pos: 0,
+            nodes: trace.nodes.iter().map(|node| (node.id, node)).collect(),
+            depth: 0,
+            functions: vec![],
+            scopes: vec![HashMap::new()],
+            undo: vec![],
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the most recent event, if any has happened yet.
+    pub fn event(&self) -> Option<&'t Event> {
+        self.pos.checked_sub(1).map(|ix| &self.trace.events[ix])
+    }
+
+    /// Moves to just after the event with sequence number `seq`.
+    pub fn seek(&mut self, seq: u64) -> Option<&'t Event> {
+        let ix = self.trace.events.iter().position(|event| event.seq == seq)?;
+        while self.pos < ix + 1 {
+            self.step_forward();
+        }
+        while self.pos > ix + 1 {
+            self.step_back();
+        }
+        self.event()
+    }
+
+    pub fn step_forward(&mut self) -> Option<&'t Event> {
+        let event = self.trace.events.get(self.pos)?;
+        let undo = self.apply(event);
+        self.undo.push(undo);
+        self.pos += 1;
+        Some(event)
+    }
+
+    pub fn step_back(&mut self) -> Option<&'t Event> {
+        let event = self.event()?;
+        if let Some(undo) = self.undo.pop() {
+            self.revert(undo);
+        }
+        self.pos -= 1;
+        Some(event)
+    }
+    /// Steps forward one event, or over a whole frame if the next event
+    /// pushes one.
+    pub fn step_over(&mut self) -> Option<&'t Event> {
+        let depth = self.depth();
+        self.step_forward()?;
+        while self.depth() > depth {
+            self.step_forward()?;
+        }
+        self.event()
+    }
+
+    /// Steps back one event, or back over a whole frame if the previous
+    /// event popped one.
+    pub fn step_over_back(&mut self) -> Option<&'t Event> {
+        let depth = self.depth();
+        let mut event = self.step_back()?;
+        while self.depth() > depth {
+            event = self.step_back()?;
+        }
+        Some(event)
+    }
+
+    /// Steps forward until the current frame is popped.
+    pub fn step_out(&mut self) -> Option<&'t Event> {
+        let depth = self.depth();
+        loop {
+            self.step_forward()?;
+            if self.depth() < depth {
+                return self.event();
+            }
+        }
+    }
+
+    /// Steps back until just before the current frame was pushed.
+    pub fn step_out_back(&mut self) -> Option<&'t Event> {
+        let depth = self.depth();
+        loop {
+            let event = self.step_back()?;
+            if self.depth() < depth {
+                return Some(event);
+            }
+        }
+    }
+
+    /// Returns how many frames are pushed at this position.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the innermost function entered at this position.
+    pub fn function(&self) -> Option<&'t str> {
+        self.functions.last().copied()
+    }
+
+    /// Returns the variables in scope in the current frame, sorted by name,
+    /// with the nodes holding their latest values.
+    ///
+    /// Nodes whose `var` is not an identifier, like `+` or `add(1, 2)`,
+    /// describe expressions rather than variables and are skipped.
+    pub fn variables(&self) -> Vec<(&'t str, &'t Node)> {
+        let mut variables = self.scopes.last().into_iter()
+            .flat_map(|scope| scope.iter().map(|(var, node)| (*var, *node)))
+            .collect::<Vec<_>>();
+        variables.sort_by_key(|(var, _)| *var);
+        variables
+    }
+
+    /// Updates the depth, functions and scopes for `event`, which is
+    /// happening, and returns how to undo that.
+    fn apply(&mut self, event: &'t Event) -> Undo<'t> {
+        match &event.kind {
+            EventKind::PushFrame => {
+                self.depth += 1;
+                self.scopes.push(HashMap::new());
+                Undo::PushFrame
+            },
+            EventKind::EnterFn{function} => {
+                self.functions.push(function.as_str());
+                self.scopes.push(HashMap::new());
+                Undo::EnterFn
+            },
+            EventKind::PopFrame => {
+                let popped = self.depth > 0;
+                self.depth = self.depth.saturating_sub(1);
+                Undo::PopFrame{popped, scope: self.pop_scope()}
+            },
+            EventKind::ExitFn => {
+                let function = self.functions.pop();
+                Undo::ExitFn{function, scope: self.pop_scope()}
+            },
+            EventKind::Define{node} => {
+                let Some(node) = self.nodes.get(node).copied() else { return Undo::Nothing };
+                let Some(var) = node.var.as_deref().filter(|var| is_ident(var)) else { return Undo::Nothing };
+                let previous = self.scopes.last_mut().unwrap().insert(var, node);
+                Undo::Define{var, previous}
+            },
+            _ => Undo::Nothing,
+        }
+    }
+
+    /// Restores the depth, functions and scopes from before the event that
+    /// returned `undo` from [Cursor::apply].
+    fn revert(&mut self, undo: Undo<'t>) {
+        match undo {
+            Undo::Nothing => {},
+            Undo::PushFrame => {
+                self.depth -= 1;
+                self.scopes.pop();
+            },
+            Undo::EnterFn => {
+                self.functions.pop();
+                self.scopes.pop();
+            },
+            Undo::PopFrame{popped, scope} => {
+                if popped {
+                    self.depth += 1;
+                }
+                self.scopes.extend(scope);
+            },
+            Undo::ExitFn{function, scope} => {
+                self.functions.extend(function);
+                self.scopes.extend(scope);
+            },
+            Undo::Define{var, previous} => {
+                let scope = self.scopes.last_mut().unwrap();
+                match previous {
+                    Some(previous) => scope.insert(var, previous),
+                    None => scope.remove(var),
+                };
+            },
+        }
+    }
+
+    /// Pops the innermost scope, unless it is the outermost one.
+    fn pop_scope(&mut self) -> Option<Scope<'t>> {
+        if self.scopes.len() > 1 { self.scopes.pop() } else { None }
+    }
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+}
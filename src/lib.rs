@@ -7,13 +7,14 @@
 //! Consider a simple program:
 //! 
 //! ```rust
+//! # use wye::*;
 //! #[wye]
 //! fn add(a: u64, b: u64) -> u64 {
 //!     a + b
 //! }
 //! 
 //! fn example() -> bool {
-//!     wyre!{add(1, add(2, 3))} == 6
+//!     (wyre!{add(1, add(2, 3))}) == 6
 //! }
 //! ```
 //! 
@@ -44,6 +45,67 @@
 //! [wye] transforms the functions it is used to annotate to record dataflow 
 //! from arguments to returned results.
 //! 
+//! On an `impl` block, an `impl Trait for T` block, or a trait, [wye]
+//! transforms every method with a body, recording `self` like any other
+//! argument and naming the methods' nodes' functions like `T::method`, so
+//! calls through trait objects and to default methods are traced too.
+//! 
+//! Functions may be generic, over types, lifetimes, or constants, with
+//! bounds inline or in where clauses, and may take `impl Trait` arguments;
+//! values of generic types are formatted according to their bounds (see
+//! [capture]).
+//!
+//! Parameters and `let`s may destructure their values with struct, tuple,
+//! tuple-struct, slice or reference patterns; each variable they bind is
+//! recorded separately, flowing from the value destructured.
+//!
+//! The conditions of `if`s and the scrutinees of `match`es and if-lets are
+//! recorded too, as are the branches they take, named `then`, `else`, or
+//! after the arms' patterns, and the variables those branches bind. Values
+//! computed in a branch get edges labeled `control` from the condition
+//! that chose it (see [trace::CONTROL]); match guards are not recorded.
+//!
+//! Each iteration of a `for`, `while`, or `loop` loop gets frames of its
+//! own, and the nodes declared during it record its index (see
+//! [trace::Node::iterations]). Items, and variables bound before the loop
+//! and assigned in it, like `total += x`, get an edge from their value in
+//! the previous iteration; `while` conditions get control edges like `if`
+//! conditions do. Iterations after the first
+//! [policy::Policy::max_iterations] are summarized by a single node.
+//!
+//! Each assignment, like `x = y`, `total += x`, or `p.y = 2`, records a new
+//! version of the variable it assigns to, with edges from its right-hand
+//! side and from the previous version; later uses of the variable get
+//! edges from the version live when they run (see [Logger::assign]).
+//!
+//! Closures are instrumented like functions, named like `main::{closure}`,
+//! with their parameters as arguments; each invocation gets a frame of its
+//! own, and its result is linked to the call the closure was passed to, if
+//! any, like the `map` in `xs.iter().map(|x| x * 2)`.
+//!
+//! The values that leave a function or closure early, with `return` or a
+//! `?` that short-circuits, and those that leave a `loop` with `break`, get
+//! edges to what they are the value of, labeled with how they left it (see
+//! [trace::RETURN], [trace::TRY] and [trace::BREAK]).
+//!
+//! `&mut` parameters and `&mut self` receivers are recorded again when the
+//! function returns, unless its result may borrow from them, and the
+//! caller's variables they borrowed hold those values from then on. Calls
+//! to other functions mutate the variables they borrow with `&mut`, and the
+//! `&mut` parameters that are their receivers, as far as wye can tell: a
+//! method call on such a parameter, like `v.len()`, whose value is the
+//! same when it is next recorded, is taken to have only read it.
+//! Other variables that are receivers, like `v` in `v.push(x)`, are not
+//! versioned by the call: later uses of `v` record its new value, but get
+//! edges from the version before it.
+//!
+//! On an inline `mod`, [wye] transforms every function and method in it
+//! and in its submodules, naming them like `net::Conn::send`, except for
+//! items marked `#[wye(skip)]` and items with [wye] attributes of their
+//! own. To instrument a module tree spread across files, see [build]; on
+//! nightly, with `#![feature(custom_inner_attributes, proc_macro_hygiene)]`,
+//! a module file can instead begin with `#![wye::wye]`.
+//! 
 //! [wyre] transforms the expressions -- typically call-sites -- that it spans
 //! to record data-flow from variables to arguments of the functions being called.
 //! 
@@ -51,14 +113,19 @@
 //! 
 //! ## Custom Formatting
 //! 
-//! [wye] and [wyre] default to printing values via [std::fmt::Display] 
-//! (technically, [ToString]) but this default choice can be overriden on a
-//! per-argument basis by giving [wye] or [wyre] an expression for each 
-//! argument to be specially printed, like:
+//! [wye] and [wyre] default to printing values via [WyeValue], for types
+//! that implement it (see [value]), or else via [std::fmt::Debug], 
+//! falling back to [std::fmt::Display], [capture::Describe], or the type's
+//! name and size for types that do not implement it (see [capture]), but
+//! this default choice can be overriden on a per-argument basis by giving
+//! [wye] or [wyre] an expression for each argument to be specially
+//! printed, like:
 //! 
 //! ```rust
+//! # use wye::*;
+//! # use std::fmt::{Debug, Display};
 //! #[wye(a: format!("{a:?}"), b: format!("{b:?}"))]
-//! fn concat(a: impl Debug + Display, b: impl Debug + Display) {
+//! fn concat(a: impl Debug + Display, b: impl Debug + Display) -> String {
 //!     format!("{a} {b}")
 //! }
 //! ```
@@ -66,29 +133,228 @@
 //! or
 //! 
 //! ```rust
+//! # use wye::*;
+//! # #[wye]
+//! # fn sum(a: u64, b: u64) -> u64 { a + b }
 //! fn add(a: u64, b: u64) -> u64 {
 //!     wyre!{ (a: format!("{a:?}"), b: format!("{b:?}"))
-//!         a + b
+//!         sum(a, b)
 //!     }
 //! }
 //! ```
 //! 
-//! # See Also
+//! Each expression sees its argument by reference. Arguments may name
+//! parameters, `let`-bindings, or, for [wyre], the caller's variables that
+//! it passes to calls; naming anything else is a compile error.
+//! 
+//! ## Skipping
+//! 
+//! Parameters, `let`-bindings, expressions, and blocks that are too big,
+//! too secret, or too boring to record can be marked with `#[wye(skip)]`
+//! or, equivalently, `#[wye::ignore]`:
+//! 
+//! ```rust
+//! # use wye::*;
+//! # fn hash(password: &str) -> u64 { password.len() as u64 }
+//! # fn check(user: &str, hash: u64) -> bool { !user.is_empty() && hash > 0 }
+//! #[wye]
+//! fn login(user: &str, #[wye(skip)] password: &str) -> bool {
+//!     #[wye(skip)]
+//!     let hash = hash(password);
+//!     let ok = #[wye::ignore] { check(user, hash) };
+//!     ok
+//! }
+//! ```
+//! 
+//! Skipped code is left uninstrumented and its value is recorded as
+//! `<skipped>`, but it still gets a node, with edges from the variables it
+//! uses, so that the causal path through it is not broken.
+//! 
+//! ## Filtering
+//! 
+//! The `include` and `exclude` arguments of [wye] select which functions and
+//! methods it instruments by name, with patterns in which `*` matches
+//! anything; a function is instrumented if it matches some included pattern,
+//! if any are given, and no excluded one:
+//! 
+//! ```rust
+//! # use wye::*;
+//! #[wye(include: "app::net::*", exclude: ["*::fmt", "app::net::tests::*"])]
+//! mod app {
+//!     // ...
+//! }
+//! ```
+//! 
+//! ## Redaction and Size Limits
+//! 
+//! Whatever is recorded, the [Logger] redacts the values of variables
+//! named like `password` or `token`, and of the expressions that use them,
+//! and of anything wrapped in [Redacted], and truncates values longer than
+//! 4 KiB. These limits can
+//! be changed with [Logger::set_policy]; see [policy].
 //! 
+//! ## Structured Values
+//! 
+//! With the `serde` feature enabled, values whose types implement
+//! `serde::Serialize` are also recorded as JSON, alongside their labels,
+//! and saved with the rest of the trace, so that they can be queried by
+//! content (see [trace]):
+//! 
+//! ```sh
+//! wye select '.status == 500' trace.json
+//! ```
+//! 
+//! # Saving Traces
+//!
+//! With the `serde` feature enabled, `Logger::dump` writes the recorded
+//! graph as JSON, which the `wye` command-line tool, which also requires
+//! the feature, can then inspect without writing any more Rust:
+//!
+//! ```sh
+//! wye render --format mermaid trace.json
+//! wye why five trace.json
+//! wye impact add::a trace.json
+//! wye select '.user == "ann"' trace.json
+//! wye diff before.json after.json
+//! wye stats < trace.json
+//! wye coverage target/wye-sites trace.json
+//! ```
+//!
+//! # See Also
+//!
 //! * [rr](https://rr-project.org)
 //! * [PANDA](https://github.com/panda-re/panda)
 //! * [pernosco](https://pernos.co)
 
-use std::{fmt::{Display}, sync::Once, collections::HashMap};
+use std::{fmt::{Display}, sync::Once, collections::{HashMap, HashSet}};
+
+pub use wye_impl::{ignore, wye, wyre, WyeValue};
+pub use policy::Redacted;
+pub use value::{Value, WyeValue};
 
-pub use wye_impl::{wye, wyre};
+pub mod build;
+pub mod capture;
+pub mod cursor;
+#[cfg(feature = "serde")]
+pub mod manifest;
+pub mod policy;
+pub mod render;
+pub mod trace;
+pub mod value;
 
 pub struct Logger {
-    graph: petgraph::graph::Graph<String, String>,
+    graph: petgraph::graph::Graph<trace::Node, String>,
     nodes: HashMap<(u64, u64), petgraph::graph::NodeIndex>,
     frames: Vec<Vec<Option<(u64, u64)>>>,
+    functions: Vec<&'static str>,
+    events: Vec<trace::Event>,
     last_node: Option<(u64, u64)>,
+    /// The values returned early by the `#[wye]` functions and closures
+    /// running, innermost last, and how; see [Logger::exit_return].
+    exits: Vec<Option<((u64, u64), &'static str)>>,
+    returned: Option<((u64, u64), &'static str)>,
+    broken: Option<((u64, u64), &'static str)>,
+    mutated: Vec<(usize, (u64, u64))>,
+    controls: Vec<(usize, (u64, u64))>,
+    loops: Vec<Loop>,
+    summaries: HashSet<petgraph::graph::NodeIndex>,
+    /// The nodes redacted for the variables they use; see
+    /// [Logger::redact_use].
+    redacted: HashSet<petgraph::graph::NodeIndex>,
+    versions: HashMap<(u64, u64), (u64, u64)>,
+    /// The versions recorded by [Logger::assign_tentatively] that are yet
+    /// to be confirmed, by binder.
+    tentative: HashMap<(u64, u64), Tentative>,
+    closure_sites: HashMap<u64, (u64, u64)>,
     epoch: u64,
+    policy: policy::Policy,
+}
+
+/// Marks the extent of a call to a `#[wye]` function; see [Logger::enter_fn].
+#[must_use]
+pub struct FnScope(());
+
+impl Drop for FnScope {
+    fn drop(&mut self) {
+        let wye = get_wye();
+        wye.functions.pop();
+        wye.returned = wye.exits.pop().flatten().or_else(|| wye.tail());
+        wye.record(trace::EventKind::ExitFn);
+    }
+}
+
+/// Marks the extent of an invocation of a closure; see
+/// [Logger::enter_closure].
+#[must_use]
+pub struct ClosureScope {
+    slot: u64,
+}
+
+impl Drop for ClosureScope {
+    fn drop(&mut self) {
+        let wye = get_wye();
+        wye.functions.pop();
+        wye.returned = wye.exits.pop().flatten().or_else(|| wye.tail());
+        if let (Some(((frame, slot), kind)), Some(&(site_frame, site_slot))) = (wye.returned, wye.closure_sites.get(&self.slot)) {
+            wye.add_link(frame, slot, site_frame, site_slot, kind);
+        }
+        wye.pop_frame();
+        wye.record(trace::EventKind::ExitFn);
+    }
+}
+
+/// Marks the extent of a branch of an `if` or `match`; see
+/// [Logger::enter_branch].
+#[must_use]
+pub struct BranchScope(());
+
+impl Drop for BranchScope {
+    fn drop(&mut self) {
+        get_wye().controls.pop();
+    }
+}
+
+/// Marks the extent of a loop; see [Logger::enter_loop].
+#[must_use]
+pub struct LoopScope(());
+
+impl Drop for LoopScope {
+    fn drop(&mut self) {
+        let wye = get_wye();
+        wye.finish_loop();
+        // a value it breaks with is the loop's:
+        let exit = wye.loops.pop().and_then(|lp| lp.exit);
+        wye.broken = exit.map(|node| (node, trace::BREAK));
+        if let Some(node) = exit {
+            wye.last_node = Some(node);
+        }
+    }
+}
+
+/// The state of a loop that is running.
+#[derive(Default)]
+struct Loop {
+    /// The index of the current iteration, if any has started.
+    index: Option<u64>,
+    /// The nodes last recorded at each slot that carries values from one
+    /// iteration to the next; see [Logger::carry].
+    carried: HashMap<u64, (u64, u64)>,
+    /// The node standing for the iterations after the first
+    /// [policy::Policy::max_iterations], once there are any.
+    summary: Option<petgraph::graph::NodeIndex>,
+    finished: bool,
+    /// The node of the value the loop breaks with, if any; see
+    /// [Logger::exit_break].
+    exit: Option<(u64, u64)>,
+}
+
+/// A version of a variable that a call may or may not have mutated; see
+/// [Logger::assign_tentatively].
+struct Tentative {
+    /// The version it replaced, if any.
+    replaced: Option<(u64, u64)>,
+    /// The node of the variable's value before the call.
+    before: (u64, u64),
 }
 
 impl Logger {
@@ -97,39 +363,92 @@ impl Logger {
             graph: petgraph::graph::Graph::new(),
             nodes: HashMap::new(),
             frames: vec![vec![]],
+            functions: vec![],
+            events: vec![],
             last_node: None,
+            exits: vec![],
+            returned: None,
+            broken: None,
+            mutated: vec![],
+            controls: vec![],
+            loops: vec![],
+            summaries: HashSet::new(),
+            redacted: HashSet::new(),
+            versions: HashMap::new(),
+            tentative: HashMap::new(),
+            closure_sites: HashMap::new(),
             epoch: 0,
+            policy: policy::Policy::default(),
         }
     }
 
-    pub fn node(&mut self, frame: u64, slot: u64, var: Option<String>, val: String) {
+    /// Sets the [policy::Policy] applied to values recorded from now on.
+    pub fn set_policy(&mut self, policy: policy::Policy) {
+        self.policy = policy;
+    }
+
+    pub fn policy(&self) -> &policy::Policy {
+        &self.policy
+    }
+
+    pub fn node(&mut self, frame: u64, slot: u64, var: Option<String>, val: impl Into<Value>) {
         self.declare_node(frame, slot);
         self.define_node(frame, slot, var, val);
     }
 
     pub fn declare_node(&mut self, frame: u64, slot: u64) {
+        let summary = self.summary();
         match self.nodes.entry((frame, slot)) {
             std::collections::hash_map::Entry::Occupied(_) => {
                 panic!("already declared node: {frame}, {slot}");
             },
             std::collections::hash_map::Entry::Vacant(ve) => {
-                let node = self.graph.add_node(Default::default());
+                // nodes of summarized iterations are all the summary node:
+                if let Some(summary) = summary {
+                    ve.insert(summary);
+                    return;
+                }
+                let node = self.graph.add_node(trace::Node{
+                    id: self.graph.node_count(),
+                    frame,
+                    slot,
+                    function: self.functions.last().map(|function| function.to_string()),
+                    iterations: self.loops.iter().filter_map(|lp| lp.index).collect(),
+                    ..Default::default()
+                });
                 ve.insert(node);
+                self.record(trace::EventKind::Declare{node: node.index()});
             },
         }
     }
 
-    pub fn define_node(&mut self, frame: u64, slot: u64, var: Option<String>, val: String) {
+    /// Defines the node at `frame`, `slot` and, if `val` has children, the
+    /// nodes of its children; see [WyeValue]. Every value is first subject
+    /// to the [policy::Policy].
+    pub fn define_node(&mut self, frame: u64, slot: u64, var: Option<String>, val: impl Into<Value>) {
+        let Value{label, long, data, children} = self.policy.apply(var.as_deref(), val.into());
         match self.nodes.entry((frame, slot)) {
             std::collections::hash_map::Entry::Occupied(oe) => {
-                let weight = var.as_ref()
-                    .map(|var| format!("{var} = {val}"))
-                    .unwrap_or_else(|| val.clone());
-                let node = oe.get();
-                let node_weight = self.graph.node_weight_mut(*node)
-                    .expect(&format!("missing node: {frame}, {slot} for update: {var:?} = {val}"));
-                *node_weight = weight;
+                let node = *oe.get();
+                if self.summaries.contains(&node) {
+                    self.last_node = Some((frame, slot));
+                    return;
+                }
+                let node_weight = self.graph.node_weight_mut(node)
+                    .unwrap_or_else(|| panic!("missing node: {frame}, {slot} for update: {var:?} = {label}"));
+                let path = var.clone().unwrap_or_default();
+                node_weight.var = var;
+                node_weight.val = label;
+                node_weight.long = long;
+                node_weight.data = data;
                 self.last_node = Some((frame, slot));
+                self.record(trace::EventKind::Define{node: node.index()});
+                self.define_children(node, &path, children);
+                if let Some(&(depth, (cond_frame, cond_slot))) = self.controls.last() {
+                    if depth == self.functions.len() {
+                        self.control(cond_frame, cond_slot, frame, slot);
+                    }
+                }
             },
             std::collections::hash_map::Entry::Vacant(_) => {
                 panic!("undefined node: {frame}, {slot}");
@@ -137,19 +456,131 @@ impl Logger {
         }
     }
 
+    /// Adds a node for each of `children`, named `{path}.{name}`, with an
+    /// edge labeled `name` from `parent`.
+    fn define_children(&mut self, parent: petgraph::graph::NodeIndex, path: &str, children: Vec<(String, Value)>) {
+        for (name, value) in children {
+            let child_path = format!("{path}.{name}");
+            let Value{label, long, data, children} = self.policy.apply(Some(&child_path), value);
+            let child = self.graph.add_node(trace::Node{
+                id: self.graph.node_count(),
+                var: Some(child_path.clone()),
+                val: label,
+                long,
+                data,
+                ..self.graph[parent].clone()
+            });
+            self.record(trace::EventKind::Declare{node: child.index()});
+            self.record(trace::EventKind::Define{node: child.index()});
+            self.graph.add_edge(parent, child, name);
+            self.record(trace::EventKind::Edge{from: parent.index(), to: child.index()});
+            self.define_children(child, &child_path, children);
+        }
+    }
+
+    /// Records where in the source the node at `frame`, `slot` comes from.
+    pub fn locate(&mut self, frame: u64, slot: u64, file: &'static str, start: (u32, u32), end: (u32, u32), text: &'static str) {
+        let node = self.nodes.get(&(frame, slot)).copied().unwrap_or_else(|| panic!("undeclared node: {frame}, {slot}"));
+        if self.summaries.contains(&node) {
+            return;
+        }
+        self.graph[node].location = Some(trace::Location{
+            file: file.into(),
+            start,
+            end,
+            text: text.into(),
+        });
+    }
+
     pub fn edge(&mut self, from_frame: u64, from_slot: u64, to_frame: u64, to_slot: u64) {
+        self.add_edge(from_frame, from_slot, to_frame, to_slot, "");
+    }
+
+    /// Adds a control edge, labeled [trace::CONTROL], from the condition or
+    /// scrutinee at `from_frame`, `from_slot` to the node at `to_frame`,
+    /// `to_slot`, whose value depends on which branch it chose.
+    pub fn control(&mut self, from_frame: u64, from_slot: u64, to_frame: u64, to_slot: u64) {
+        self.add_edge(from_frame, from_slot, to_frame, to_slot, trace::CONTROL);
+    }
+
+    fn add_edge(&mut self, from_frame: u64, from_slot: u64, to_frame: u64, to_slot: u64, label: &str) {
+        self.tentative.remove(&(from_frame, from_slot));
+        if label.is_empty() {
+            self.redact_use((from_frame, from_slot), (to_frame, to_slot));
+        }
+        let (from_frame, from_slot) = self.live(from_frame, from_slot);
         let from = self.nodes.get(&(from_frame, from_slot)).copied().unwrap_or_else(|| panic!("no entry found for from key: {from_frame}, {from_slot}"));
         let to = self.nodes.get(&(to_frame, to_slot)).copied().unwrap_or_else(|| panic!("no entry found for to key: {to_frame}, {to_slot}"));
-        self.graph.add_edge(from, to, "".into());
+        // summaries stand for many nodes, but get each edge only once:
+        let summarized = self.summaries.contains(&from) || self.summaries.contains(&to);
+        if summarized && (from == to || self.graph.find_edge(from, to).is_some()) {
+            return;
+        }
+        self.graph.add_edge(from, to, label.into());
+        self.record(trace::EventKind::Edge{from: from.index(), to: to.index()});
+    }
+
+    /// Like [Logger::edge], but does nothing if the edge already exists.
+    pub fn link(&mut self, from_frame: u64, from_slot: u64, to_frame: u64, to_slot: u64) {
+        self.add_link(from_frame, from_slot, to_frame, to_slot, "");
+    }
+
+    /// Like [Logger::link], but labels the edge `kind`, how the value at
+    /// `from_frame`, `from_slot` left the function, closure or loop whose
+    /// value it is, as returned by [Logger::take_returned] and
+    /// [Logger::take_broken].
+    pub fn exit_edge(&mut self, from_frame: u64, from_slot: u64, to_frame: u64, to_slot: u64, kind: &str) {
+        self.add_link(from_frame, from_slot, to_frame, to_slot, kind);
+    }
+
+    fn add_link(&mut self, from_frame: u64, from_slot: u64, to_frame: u64, to_slot: u64, label: &str) {
+        self.tentative.remove(&(from_frame, from_slot));
+        if label.is_empty() {
+            self.redact_use((from_frame, from_slot), (to_frame, to_slot));
+        }
+        let (from_frame, from_slot) = self.live(from_frame, from_slot);
+        let from = self.nodes.get(&(from_frame, from_slot)).copied().unwrap_or_else(|| panic!("no entry found for from key: {from_frame}, {from_slot}"));
+        let to = self.nodes.get(&(to_frame, to_slot)).copied().unwrap_or_else(|| panic!("no entry found for to key: {to_frame}, {to_slot}"));
+        if self.graph.find_edge(from, to).is_none() {
+            self.add_edge(from_frame, from_slot, to_frame, to_slot, label);
+        }
+    }
+
+    /// Redacts the node at `to`, which gets its value from the node at
+    /// `from`, if `from` is a variable that is redacted, by name or for a
+    /// value it was bound to, like `password` in `password.trim()`, or if
+    /// `from` was itself redacted and `to` is a variable, like a parameter
+    /// that `&password` is passed to.
+    fn redact_use(&mut self, from: (u64, u64), to: (u64, u64)) {
+        let (Some(&from), Some(&to)) = (self.nodes.get(&from), self.nodes.get(&to)) else { return };
+        if self.summaries.contains(&to) {
+            return;
+        }
+        let from_var = self.graph[from].var.as_deref().and_then(policy::name);
+        let from_redacted = self.redacted.contains(&from);
+        let uses_redacted = from_var.is_some_and(|var| from_redacted || self.policy.redacts(var));
+        let is_var = self.graph[to].var.as_deref().and_then(policy::name).is_some();
+        if !uses_redacted && !(from_redacted && is_var) {
+            return;
+        }
+        let node = &mut self.graph[to];
+        node.val = policy::REDACTED.into();
+        node.long = None;
+        node.data = None;
+        self.redacted.insert(to);
     }
 
     pub fn push_frame(&mut self) {
+        self.returned = None;
+        self.mutated.clear();
         self.frames.push(vec![]);
+        self.record(trace::EventKind::PushFrame);
     }
 
     pub fn pop_frame(&mut self) {
         self.frames.pop();
         self.epoch += 1;
+        self.record(trace::EventKind::PopFrame);
     }
 
     pub fn push_lit(&mut self) {
@@ -171,6 +602,274 @@ impl Logger {
     pub fn set_last_node(&mut self, addr: (u64, u64)) {
         self.last_node = Some(addr);
     }
+
+    /// Returns the node of the value of the `#[wye]` function that returned
+    /// most recently, if any did since the last [Logger::push_frame] or call
+    /// to this method, so that calls can tell instrumented callees apart,
+    /// and how it left the function: its last node, unlabeled, unless it
+    /// returned early (see [Logger::exit_return]).
+    pub fn take_returned(&mut self) -> Option<((u64, u64), &'static str)> {
+        self.returned.take()
+    }
+
+    /// Returns the node of the value that the loop that finished most
+    /// recently broke with, if any, labeled [trace::BREAK]; see
+    /// [Logger::exit_break].
+    pub fn take_broken(&mut self) -> Option<((u64, u64), &'static str)> {
+        self.broken.take()
+    }
+
+    /// Returns the last node, as the value of the function or closure
+    /// returning, labeled [trace::BREAK] if it is the value a loop just
+    /// broke with, like that of a function whose tail is a `loop`.
+    fn tail(&self) -> Option<((u64, u64), &'static str)> {
+        let node = self.last_node?;
+        match self.broken {
+            Some((broken, kind)) if broken == node => Some((node, kind)),
+            _ => Some((node, "")),
+        }
+    }
+
+    /// Records the node at `frame`, `slot` as the value that the innermost
+    /// `#[wye]` function or closure returns with `return`, which its caller
+    /// gets an edge from, labeled [trace::RETURN], instead of its last node.
+    pub fn exit_return(&mut self, frame: u64, slot: u64) {
+        if let Some(exit) = self.exits.last_mut() {
+            *exit = Some(((frame, slot), trace::RETURN));
+        }
+    }
+
+    /// Like [Logger::exit_return], for the node at `frame`, `slot` of the
+    /// operand of a `?`, which returns it, or rather its `Err` or `None`,
+    /// if it short-circuits, labeled [trace::TRY]; if it does not, the `?`
+    /// calls [Logger::resume].
+    pub fn exit_try(&mut self, frame: u64, slot: u64) {
+        if let Some(exit) = self.exits.last_mut() {
+            *exit = Some(((frame, slot), trace::TRY));
+        }
+    }
+
+    /// Forgets the value recorded by [Logger::exit_try], once the `?` it
+    /// was recorded for has not returned it.
+    pub fn resume(&mut self) {
+        if let Some(exit) = self.exits.last_mut() {
+            *exit = None;
+        }
+    }
+
+    /// Records the node at `frame`, `slot` as the value that a `loop`,
+    /// `depth` loops out from the innermost loop, breaks with; it is the
+    /// last node once the loop finishes, and [Logger::take_broken] returns
+    /// it.
+    pub fn exit_break(&mut self, depth: usize, frame: u64, slot: u64) {
+        let Some(index) = self.loops.len().checked_sub(depth + 1) else { return };
+        self.loops[index].exit = Some((frame, slot));
+    }
+
+    /// Records the node at `frame`, `slot` as the value, on exit, of the
+    /// `&mut` parameter or receiver at `index` of the `#[wye]` function
+    /// returning, which its caller takes with [Logger::take_mutated].
+    pub fn mutated(&mut self, index: usize, frame: u64, slot: u64) {
+        self.mutated.push((index, (frame, slot)));
+    }
+
+    /// Returns the parameters recorded by [Logger::mutated] since the last
+    /// [Logger::push_frame], by index, with the nodes of their values on
+    /// exit, which the caller's variables they borrowed now hold.
+    pub fn take_mutated(&mut self) -> Vec<(usize, (u64, u64))> {
+        std::mem::take(&mut self.mutated)
+    }
+
+    /// Attributes nodes declared until the returned [FnScope] is dropped
+    /// to the function `name`.
+    pub fn enter_fn(&mut self, name: &'static str) -> FnScope {
+        self.functions.push(name);
+        self.exits.push(None);
+        self.record(trace::EventKind::EnterFn{function: name.into()});
+        FnScope(())
+    }
+
+    /// Records that the closure at `slot` was created as an argument of the
+    /// call at `site`, to which each of its invocations is linked.
+    pub fn closure_site(&mut self, slot: u64, site: (u64, u64)) {
+        self.closure_sites.insert(slot, site);
+    }
+
+    /// Returns the arguments the closure at `slot` is being invoked with:
+    /// those of the current frame, if it is called directly, like `f(x)`,
+    /// and none if it was passed to a call, which invokes it with values
+    /// that are not recorded.
+    pub fn closure_args(&self, slot: u64) -> Vec<Option<(u64, u64)>> {
+        if self.closure_sites.contains_key(&slot) {
+            return vec![];
+        }
+        self.frames.last().cloned().unwrap_or_default()
+    }
+
+    /// Starts an invocation of the closure at `slot`, in the function
+    /// `name`, which gets a frame of its own, like a call to a `#[wye]`
+    /// function, until the returned [ClosureScope] is dropped; its last
+    /// node is then linked to the call the closure was passed to, if any
+    /// (see [Logger::closure_site]).
+    pub fn enter_closure(&mut self, name: &'static str, slot: u64) -> ClosureScope {
+        self.functions.push(name);
+        self.exits.push(None);
+        self.record(trace::EventKind::EnterFn{function: name.into()});
+        self.push_frame();
+        ClosureScope{slot}
+    }
+
+    /// Adds a [control](Logger::control) edge from the condition or
+    /// scrutinee at `cond` to every node defined in the current function
+    /// until the returned [BranchScope] is dropped, except in branches
+    /// nested inside this one, whose nodes depend on their own conditions.
+    pub fn enter_branch(&mut self, cond: (u64, u64)) -> BranchScope {
+        self.controls.push((self.functions.len(), cond));
+        BranchScope(())
+    }
+
+    /// Records the node at `frame`, `slot`, which holds the value just
+    /// assigned to the variable bound at `binder_frame`, `binder_slot`, as
+    /// that variable's live version: edges from the binder come from it
+    /// until the variable is next assigned.
+    pub fn assign(&mut self, binder_frame: u64, binder_slot: u64, frame: u64, slot: u64) {
+        self.tentative.remove(&(binder_frame, binder_slot));
+        self.versions.insert((binder_frame, binder_slot), (frame, slot));
+    }
+
+    /// Like [Logger::assign], for a call that may or may not have mutated
+    /// the variable, whose value before it is the node at `before_frame`,
+    /// `before_slot`: if the variable is next [observed](Logger::observe)
+    /// with the same value, the version it replaced is live again.
+    /// Edges from the variable in between confirm the call's version.
+    pub fn assign_tentatively(&mut self, binder_frame: u64, binder_slot: u64, frame: u64, slot: u64, before_frame: u64, before_slot: u64) {
+        let replaced = self.versions.get(&(binder_frame, binder_slot)).copied();
+        self.assign(binder_frame, binder_slot, frame, slot);
+        self.tentative.insert((binder_frame, binder_slot), Tentative{replaced, before: (before_frame, before_slot)});
+    }
+
+    /// Checks the version of the variable bound at `binder_frame`,
+    /// `binder_slot` that [Logger::assign_tentatively] recorded, if any,
+    /// against its value, just recorded at `frame`, `slot`, undoing it if
+    /// the value is the same as before.
+    pub fn observe(&mut self, binder_frame: u64, binder_slot: u64, frame: u64, slot: u64) {
+        let Some(Tentative{replaced, before}) = self.tentative.remove(&(binder_frame, binder_slot)) else { return };
+        if !self.same_value(before, (frame, slot)) {
+            return;
+        }
+        match replaced {
+            Some(version) => self.versions.insert((binder_frame, binder_slot), version),
+            None => self.versions.remove(&(binder_frame, binder_slot)),
+        };
+    }
+
+    /// Returns whether the nodes at `a` and `b` record the same value of
+    /// the same place, neither of them being a summary.
+    fn same_value(&self, a: (u64, u64), b: (u64, u64)) -> bool {
+        let (Some(&a), Some(&b)) = (self.nodes.get(&a), self.nodes.get(&b)) else { return false };
+        if self.summaries.contains(&a) || self.summaries.contains(&b) {
+            return false;
+        }
+        let (a, b) = (&self.graph[a], &self.graph[b]);
+        (&a.var, &a.val, &a.long, &a.data) == (&b.var, &b.val, &b.long, &b.data)
+    }
+
+    /// Returns the address of the live version of the variable bound at
+    /// `frame`, `slot`, if it has been assigned, and otherwise of its binder.
+    pub fn live(&self, frame: u64, slot: u64) -> (u64, u64) {
+        self.versions.get(&(frame, slot)).copied().unwrap_or((frame, slot))
+    }
+
+    /// Starts a loop, whose iterations, started by [Logger::next_iteration],
+    /// each get frames of their own, and whose index is recorded in the
+    /// [trace::Node::iterations] of the nodes declared during them, until the
+    /// returned [LoopScope] is dropped.
+    ///
+    /// Iterations after the first [policy::Policy::max_iterations] are
+    /// summarized: the nodes declared during them are all one node, labeled
+    /// with how many iterations it stands for.
+    pub fn enter_loop(&mut self) -> LoopScope {
+        self.loops.push(Loop::default());
+        LoopScope(())
+    }
+
+    /// Starts the next iteration of the innermost loop.
+    pub fn next_iteration(&mut self) {
+        self.epoch += 1;
+        let Some(lp) = self.loops.last_mut() else { return };
+        let index = lp.index.map_or(0, |index| index + 1);
+        lp.index = Some(index);
+        self.record(trace::EventKind::Iterate{index});
+    }
+
+    /// Adds an edge to the node at `frame`, `slot`, which carries a value,
+    /// like an iterator's item, from one iteration of the innermost loop to
+    /// the next, from the node last carried at `slot`, if
+    /// any, and returns whether there was one.
+    pub fn carry(&mut self, frame: u64, slot: u64) -> bool {
+        let Some(lp) = self.loops.last_mut() else { return false };
+        match lp.carried.insert(slot, (frame, slot)) {
+            Some((prev_frame, prev_slot)) => {
+                self.edge(prev_frame, prev_slot, frame, slot);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Ends the iterations of the innermost loop, so that what follows it
+    /// is recorded even if its last iterations were summarized.
+    pub fn finish_loop(&mut self) {
+        let Some(lp) = self.loops.last_mut().filter(|lp| !lp.finished) else { return };
+        lp.finished = true;
+        let (Some(summary), Some(index), Some(max)) = (lp.summary, lp.index, self.policy.max_iterations) else { return };
+        self.graph[summary].val = format!("<{} more iterations>", index + 1 - max);
+        self.record(trace::EventKind::Define{node: summary.index()});
+    }
+
+    /// Returns the node standing for the iterations being summarized, if
+    /// some loop is past [policy::Policy::max_iterations].
+    fn summary(&mut self) -> Option<petgraph::graph::NodeIndex> {
+        let max = self.policy.max_iterations?;
+        let lp = self.loops.iter_mut().find(|lp| !lp.finished && lp.index.is_some_and(|index| index >= max))?;
+        if let Some(summary) = lp.summary {
+            return Some(summary);
+        }
+        let summary = self.graph.add_node(trace::Node{
+            id: self.graph.node_count(),
+            function: self.functions.last().map(|function| function.to_string()),
+            val: "<more iterations>".into(),
+            ..Default::default()
+        });
+        lp.summary = Some(summary);
+        self.summaries.insert(summary);
+        self.record(trace::EventKind::Declare{node: summary.index()});
+        Some(summary)
+    }
+
+    fn record(&mut self, kind: trace::EventKind) {
+        let seq = self.events.len() as u64;
+        self.events.push(trace::Event{seq, kind});
+    }
+
+    pub fn trace(&self) -> trace::Trace {
+        trace::Trace{
+            nodes: self.graph.node_weights().cloned().collect(),
+            edges: self.graph.raw_edges().iter().map(|edge| trace::Edge{
+                from: edge.source().index(),
+                to: edge.target().index(),
+                label: edge.weight.clone(),
+            }).collect(),
+            events: self.events.clone(),
+        }
+    }
+
+    /// Writes the recorded graph as a JSON [trace::Trace] for later
+    /// inspection, e.g., by the `wye` command-line tool.
+    #[cfg(feature = "serde")]
+    pub fn dump(&self, writer: impl std::io::Write) -> std::io::Result<()> {
+        self.trace().dump(writer)
+    }
 }
 
 impl Display for Logger {
@@ -180,14 +879,42 @@ impl Display for Logger {
     }
 }
 
+/// Items that code generated by [wye] for modules refers to unqualified.
+#[doc(hidden)]
+pub mod prelude {
+    pub use crate::{capture, get_wye};
+}
+
+/// Behaves like [std::dbg], except that it prints `text`, the source of
+/// each argument as written, rather than that of the instrumented argument
+/// that [wye] evaluates in its place.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dbg {
+    () => {
+        ::std::eprintln!("[{}:{}:{}]", ::std::file!(), ::std::line!(), ::std::column!())
+    };
+    ($text:expr => $val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                ::std::eprintln!("[{}:{}:{}] {} = {:#?}", ::std::file!(), ::std::line!(), ::std::column!(), $text, &tmp);
+                tmp
+            }
+        }
+    };
+    ($($text:expr => $val:expr),+ $(,)?) => {
+        ($($crate::__dbg!($text => $val)),+,)
+    };
+}
+
 static mut WYE: Option<Logger> = None;
-static mut INIT: std::sync::Once = Once::new();
+static INIT: std::sync::Once = Once::new();
 
 pub fn get_wye<'a>() -> &'a mut Logger {
     unsafe {
         INIT.call_once(|| {
             WYE = Some(Logger::new());
         });
-        WYE.as_mut().unwrap()
+        (*std::ptr::addr_of_mut!(WYE)).as_mut().unwrap()
     }
 }
\ No newline at end of file
@@ -0,0 +1,137 @@
+//! # Overview
+//!
+//! [Manifest] reads the site manifests written by the [wye](crate::wye)
+//! and [wyre](crate::wyre) macros, which describe every instrumented site
+//! whether or not it ever executed.
+//!
+//! To get a manifest, either give the crate being instrumented a build
+//! script, so that the macros write to `$OUT_DIR/wye-sites`, or set the
+//! `WYE_MANIFEST_DIR` environment variable while building it:
+//!
+//! ```sh
+//! WYE_MANIFEST_DIR=target/wye-sites cargo build
+//! wye coverage target/wye-sites trace.json
+//! ```
+//!
+//! Reading manifests requires the `serde` feature.
+//!
+//! Every build rewrites the manifest files of the macro invocations it
+//! expands, but files for invocations that have since been deleted or
+//! moved are left behind until the directory is cleaned.
+
+use std::{collections::{BTreeMap, HashMap}, fmt::Display, fs, io::{self, BufRead}, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::trace::Trace;
+
+/// Static metadata about one instrumented site.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Site {
+    /// The site ID, which is also the `slot` of every node it records.
+    pub id: u64,
+    #[serde(rename = "crate")]
+    pub krate: String,
+    pub function: Option<String>,
+    pub file: String,
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+    pub text: String,
+    /// The syntactic kind of the site, like `Expr::Call`,
+    /// `Expr::Binary(+)`, or `Param`.
+    pub kind: String,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Manifest {
+    pub sites: BTreeMap<u64, Site>,
+}
+
+/// How many times each site of a [Manifest] executed in a [Trace].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Coverage {
+    pub sites: Vec<(Site, usize)>,
+}
+
+impl Manifest {
+    /// Reads one manifest file of JSON lines.
+    pub fn load(reader: impl BufRead) -> io::Result<Self> {
+        let mut manifest = Manifest::default();
+        manifest.extend(reader)?;
+        Ok(manifest)
+    }
+
+    /// Reads a manifest file, or every `.jsonl` file in a manifest directory.
+    pub fn load_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut manifest = Manifest::default();
+        if path.is_dir() {
+            let mut entries = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<io::Result<Vec<_>>>()?;
+            entries.sort();
+            for entry in entries.iter().filter(|entry| entry.extension().is_some_and(|ext| ext == "jsonl")) {
+                manifest.extend(io::BufReader::new(fs::File::open(entry)?))?;
+            }
+        } else {
+            manifest.extend(io::BufReader::new(fs::File::open(path)?))?;
+        }
+        Ok(manifest)
+    }
+
+    fn extend(&mut self, reader: impl BufRead) -> io::Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let site: Site = serde_json::from_str(&line)?;
+            self.sites.insert(site.id, site);
+        }
+        Ok(())
+    }
+
+    pub fn coverage(&self, trace: &Trace) -> Coverage {
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+        for node in &trace.nodes {
+            *counts.entry(node.slot).or_default() += 1;
+        }
+        let mut sites = self.sites.values()
+            .map(|site| (site.clone(), counts.get(&site.id).copied().unwrap_or_default()))
+            .collect::<Vec<_>>();
+        sites.sort_by(|(a, _), (b, _)| (&a.file, a.start, a.end).cmp(&(&b.file, b.start, b.end)));
+        Coverage{sites}
+    }
+}
+
+impl Display for Site {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{} {} {}", self.file, self.start.0, self.start.1, self.kind, self.text)
+    }
+}
+
+impl Display for Coverage {
+    /// Summarizes coverage per function, then lists the sites that never
+    /// executed.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut functions: BTreeMap<(&str, Option<&str>), (usize, usize)> = BTreeMap::new();
+        for (site, count) in &self.sites {
+            let entry = functions.entry((&site.krate, site.function.as_deref())).or_default();
+            entry.1 += 1;
+            if *count > 0 {
+                entry.0 += 1;
+            }
+        }
+        let covered = self.sites.iter().filter(|(_, count)| *count > 0).count();
+        writeln!(f, "sites: {covered}/{} executed", self.sites.len())?;
+        writeln!(f, "functions:")?;
+        for ((krate, function), (covered, total)) in &functions {
+            writeln!(f, "    {krate}::{}: {covered}/{total}", function.unwrap_or("<toplevel>"))?;
+        }
+        writeln!(f, "never executed:")?;
+        for (site, _) in self.sites.iter().filter(|(_, count)| *count == 0) {
+            writeln!(f, "    {site}")?;
+        }
+        Ok(())
+    }
+}
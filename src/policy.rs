@@ -0,0 +1,170 @@
+//! # Overview
+//!
+//! A [Policy] limits what the [Logger](crate::Logger) keeps of the values
+//! it records, so that secrets and huge values never reach a trace, a DOT
+//! graph, or any other export:
+//!
+//! * values of variables and fields whose names contain one of
+//!   [Policy::redact], ignoring case, are replaced by [REDACTED], and so
+//!   are the values of expressions that use those variables, like
+//!   `&password` or `password.trim()`, and of the variables bound to any
+//!   of these, like the parameters they are passed to,
+//! * labels and long forms longer than [Policy::max_len] bytes are
+//!   truncated, with a marker saying how much was cut, and structured
+//!   values whose JSON is longer are dropped,
+//! * iterations of a loop after its first [Policy::max_iterations] are
+//!   summarized by a single node; see [Logger::enter_loop](crate::Logger::enter_loop).
+//!
+//! Values wrapped in [Redacted] are always replaced by [REDACTED].
+//!
+//! # Example
+//!
+//! ```rust
+//! use wye::{get_wye, policy::Policy, Redacted};
+//!
+//! get_wye().set_policy(Policy{max_len: Some(14), ..Default::default()});
+//! get_wye().node(0, 1, Some("api_token".into()), "abc123");
+//! get_wye().node(0, 2, Some("list".into()), format!("{:?}", vec![0; 100]));
+//! get_wye().node(0, 3, None, wye::Value::new(&Redacted("abc123")));
+//!
+//! let vals: Vec<_> = get_wye().trace().nodes.into_iter().map(|node| node.val).collect();
+//! assert_eq!(vals, ["<redacted>", "[0, 0, 0, 0, 0...<286 more bytes>", "<redacted>"]);
+//! ```
+
+use std::ops::{Deref, DerefMut};
+
+use crate::value::{Value, WyeValue};
+
+/// What redacted values are recorded as.
+pub const REDACTED: &str = "<redacted>";
+
+/// Limits on recorded values; see above.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Policy {
+    /// The longest label or long form kept, in bytes, if any.
+    pub max_len: Option<usize>,
+    /// Substrings of variable names whose values are redacted.
+    pub redact: Vec<String>,
+    /// The most iterations of a loop recorded one by one, if any.
+    pub max_iterations: Option<u64>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self{
+            max_len: Some(4096),
+            redact: ["password", "passwd", "token", "api_key"].map(String::from).to_vec(),
+            max_iterations: Some(100),
+        }
+    }
+}
+
+impl Policy {
+    /// A policy that keeps every value as is.
+    pub fn none() -> Self {
+        Self{max_len: None, redact: vec![], max_iterations: None}
+    }
+
+    /// Returns whether the value of `var` should be redacted.
+    ///
+    /// Only names are matched, like `password` or, for the fields of
+    /// [WyeValue]s, `login.password`, not the source text of expressions;
+    /// the [Logger](crate::Logger) redacts the expressions that use
+    /// redacted variables as it records their edges.
+    pub fn redacts(&self, var: &str) -> bool {
+        let Some(name) = name(var) else { return false };
+        let name = name.to_lowercase();
+        self.redact.iter().any(|pattern| name.contains(&pattern.to_lowercase()))
+    }
+
+    /// Applies this policy to `value`, the value of `var`, but not to its
+    /// children, which the [Logger](crate::Logger) records, and so checks,
+    /// one by one.
+    pub fn apply(&self, var: Option<&str>, value: Value) -> Value {
+        if var.is_some_and(|var| self.redacts(var)) {
+            return Value::from(REDACTED);
+        }
+        let Some(max_len) = self.max_len else { return value };
+        Value{
+            label: truncate(value.label, max_len),
+            long: value.long.map(|long| truncate(long, max_len)),
+            #[cfg(feature = "serde")]
+            data: value.data.filter(|data| data.to_string().len() <= max_len),
+            #[cfg(not(feature = "serde"))]
+            data: value.data,
+            children: value.children,
+        }
+    }
+}
+
+/// Returns the name of the variable or field `var`, like `password` in
+/// `login.password`, or none if it is the source text of an expression.
+pub(crate) fn name(var: &str) -> Option<&str> {
+    let name = var.rsplit('.').next().unwrap_or(var);
+    let is_name = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    is_name.then_some(name)
+}
+
+/// Truncates `text` to at most `max_len` bytes, on a character boundary,
+/// followed by a marker saying how many bytes were cut.
+fn truncate(mut text: String, max_len: usize) -> String {
+    if text.len() <= max_len {
+        return text;
+    }
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let cut = text.len() - end;
+    text.truncate(end);
+    text + &format!("...<{cut} more bytes>")
+}
+
+/// Wraps a value that must never be recorded, printed, or serialized.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Redacted<T>(pub T);
+
+impl<T> Redacted<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Redacted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Redacted<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Redacted<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> std::fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> WyeValue for Redacted<T> {
+    fn label(&self) -> String {
+        REDACTED.into()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Redacted<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
@@ -0,0 +1,131 @@
+//! # Overview
+//!
+//! Renderers for [Trace]s.
+//!
+//! [Format::Dot] matches the output of `Display for Logger`, except that
+//! node ids are preserved when rendering a slice of a larger trace.
+
+use std::{fmt::Write, str::FromStr};
+
+use crate::trace::{Trace, Node};
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+    Dot,
+    Mermaid,
+    Html,
+    #[default]
+    Text,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Format::Dot),
+            "mermaid" => Ok(Format::Mermaid),
+            "html" => Ok(Format::Html),
+            "text" => Ok(Format::Text),
+            _ => Err(format!("unknown format: {s} (expected one of: dot, mermaid, html, text)")),
+        }
+    }
+}
+
+pub fn render(trace: &Trace, format: Format) -> String {
+    match format {
+        Format::Dot => dot(trace),
+        Format::Mermaid => mermaid(trace),
+        Format::Html => html(trace),
+        Format::Text => text(trace),
+    }
+}
+
+pub fn dot(trace: &Trace) -> String {
+    fn escape(s: &str) -> String {
+        let mut out = String::new();
+        for c in s.chars() {
+            match c {
+                '"' | '\\' => { out.push('\\'); out.push(c); },
+                '\n' => out.push_str("\\l"),
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+    let mut out = String::from("digraph {\n");
+    for node in &trace.nodes {
+        writeln!(out, "    {} [ label = \"{}\" ]", node.id, escape(&node.to_string())).unwrap();
+    }
+    for edge in &trace.edges {
+        writeln!(out, "    {} -> {} [ label = \"{}\" ]", edge.from, edge.to, escape(&edge.label)).unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+pub fn mermaid(trace: &Trace) -> String {
+    fn escape(s: &str) -> String {
+        s.replace('&', "&amp;").replace('"', "#quot;").replace('\n', "<br>")
+    }
+    let mut out = String::from("flowchart TD\n");
+    for node in &trace.nodes {
+        writeln!(out, "    n{}[\"{}\"]", node.id, escape(&node.to_string())).unwrap();
+    }
+    for edge in &trace.edges {
+        if edge.label.is_empty() {
+            writeln!(out, "    n{} --> n{}", edge.from, edge.to).unwrap();
+        } else {
+            writeln!(out, "    n{} -->|\"{}\"| n{}", edge.from, escape(&edge.label), edge.to).unwrap();
+        }
+    }
+    out
+}
+
+pub fn html(trace: &Trace) -> String {
+    let diagram = mermaid(trace)
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>wye trace</title>
+<script type="module">
+import mermaid from "https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.esm.min.mjs";
+mermaid.initialize({{ startOnLoad: true }});
+</script>
+</head>
+<body>
+<pre class="mermaid">
+{diagram}</pre>
+</body>
+</html>
+"#)
+}
+
+/// Renders one line per node, listing the ids of the node's inputs and,
+/// when known, where in the source the node comes from.
+pub fn text(trace: &Trace) -> String {
+    let mut out = String::new();
+    for node in &trace.nodes {
+        let inputs = trace.edges.iter()
+            .filter(|edge| edge.to == node.id)
+            .map(|edge| edge.from.to_string())
+            .collect::<Vec<_>>();
+        write!(out, "{} [{}] {node}", node.id, function(node)).unwrap();
+        if !inputs.is_empty() {
+            write!(out, " <- {}", inputs.join(", ")).unwrap();
+        }
+        if let Some(location) = &node.location {
+            write!(out, " @ {location}").unwrap();
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn function(node: &Node) -> &str {
+    node.function.as_deref().unwrap_or("<toplevel>")
+}
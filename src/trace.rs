@@ -0,0 +1,383 @@
+//! # Overview
+//!
+//! [Trace] is a snapshot of the causal graph recorded by a
+//! [Logger](crate::Logger) which, with the `serde` feature, can be saved to
+//! disk and inspected later, for example with the `wye` command-line tool.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[cfg(feature = "serde")] {
+//! use wye::get_wye;
+//!
+//! let mut buf = vec![];
+//! get_wye().dump(&mut buf).unwrap();
+//! let trace = wye::trace::Trace::load(&buf[..]).unwrap();
+//! assert_eq!(trace, get_wye().trace());
+//! # }
+//! ```
+//!
+//! # Node Selectors
+//!
+//! [Trace::select] resolves textual node selectors like:
+//!
+//! * `12` -- the node with id 12,
+//! * `five` -- every node binding a variable named `five`,
+//! * `add::a` -- every node binding a variable named `a` in function `add`,
+//! * `.status` -- every node whose structured value has a `status` field,
+//! * `.status == 500` -- every node whose structured value's `status` field
+//!   is 500.
+//!
+//! Structured values are only recorded with the `serde` feature; see
+//! [capture](crate::capture#structured-values). Paths like `.items.0.id`
+//! index into nested objects and arrays, `.` alone is the whole value, and
+//! the right-hand side of `==` is JSON, or else a string, so `.user == ann`
+//! and `.user == "ann"` are the same query.
+
+use std::{borrow::Cow, collections::{BTreeMap, HashMap, HashSet}, fmt::Display};
+
+use petgraph::{graph::{Graph, NodeIndex}, visit::{Dfs, Reversed, Walker}};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::value::Data;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trace {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    /// Everything the [Logger](crate::Logger) recorded, in order; see
+    /// [Cursor](crate::cursor::Cursor).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub events: Vec<Event>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Node {
+    pub id: usize,
+    pub frame: u64,
+    pub slot: u64,
+    pub function: Option<String>,
+    pub var: Option<String>,
+    pub val: String,
+    /// The long form of `val`, if any; see [WyeValue](crate::WyeValue).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub long: Option<String>,
+    /// The structured form of `val`, if any; see [Value](crate::Value).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub data: Option<Data>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub location: Option<Location>,
+    /// The indices of the iterations of the loops running when the node was
+    /// declared, outermost first.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub iterations: Vec<u64>,
+}
+
+/// Where in the instrumented source a node's value was computed.
+///
+/// Lines and columns are 1-based; `end` points just past the last
+/// character of `text`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Location {
+    pub file: Cow<'static, str>,
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+    pub text: Cow<'static, str>,
+}
+
+impl Location {
+    /// Returns the site ID of this location in crate `krate`.
+    pub fn site_id(&self, krate: &str) -> u64 {
+        site_id(krate, &self.file, self.start, self.end)
+    }
+}
+
+/// Computes the stable site ID that `#[wye]` and `wyre!` assign to the
+/// source span from `start` to `end` in `file` of crate `krate`.
+///
+/// Site IDs are the 64-bit FNV-1a hash of `krate`, a `0` byte, `file`, a
+/// `0` byte, and then the start line, start column, end line, and end
+/// column as little-endian `u32`s. They are specified here rather than by
+/// [std::hash::Hash] so that they are the same across builds, machines, and
+/// compiler versions.
+pub fn site_id(krate: &str, file: &str, start: (u32, u32), end: (u32, u32)) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    let numbers = [start.0, start.1, end.0, end.1];
+    let bytes = krate.bytes()
+        .chain([0])
+        .chain(file.bytes())
+        .chain([0])
+        .chain(numbers.iter().flat_map(|n| n.to_le_bytes()));
+    for byte in bytes {
+        h ^= byte as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    h
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.start.0, self.start.1)
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.var {
+            Some(var) => write!(f, "{var} = {}", self.val),
+            None => write!(f, "{}", self.val),
+        }
+    }
+}
+
+/// The label of control edges, from the conditions of `if`s and the
+/// scrutinees of `match`es to the values computed in the branches they
+/// chose; data edges are unlabeled, and edges to a structured value's
+/// children are labeled with the children's names.
+pub const CONTROL: &str = "control";
+
+/// The label of edges from the values that functions and closures return
+/// early, with `return`, to the calls they return to.
+pub const RETURN: &str = "return";
+
+/// The label of edges from the operands of `?`s that short-circuit, whose
+/// `Err` or `None` functions and closures return, to the calls they return
+/// to.
+pub const TRY: &str = "?";
+
+/// The label of edges from the values that `loop`s break with to what
+/// they are the values of.
+pub const BREAK: &str = "break";
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub label: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Event {
+    /// Monotonically increasing, starting from 0, across the whole run.
+    pub seq: u64,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: EventKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum EventKind {
+    PushFrame,
+    PopFrame,
+    EnterFn { function: String },
+    ExitFn,
+    Declare { node: usize },
+    Define { node: usize },
+    Edge { from: usize, to: usize },
+    /// Starts the iteration `index` of the innermost loop.
+    Iterate { index: u64 },
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// Follow edges backwards, towards the causes of the selected nodes.
+    Why,
+    /// Follow edges forwards, towards the effects of the selected nodes.
+    Impact,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub nodes: usize,
+    pub edges: usize,
+    pub frames: BTreeMap<u64, usize>,
+    pub functions: BTreeMap<Option<String>, usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum Change {
+    Added(Node),
+    Removed(Node),
+    Changed(Node, Node),
+}
+
+impl Trace {
+    #[cfg(feature = "serde")]
+    pub fn load(reader: impl std::io::Read) -> std::io::Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn dump(&self, writer: impl std::io::Write) -> std::io::Result<()> {
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+
+    pub fn graph(&self) -> (Graph<&Node, &str>, HashMap<usize, NodeIndex>) {
+        let mut graph = Graph::new();
+        let mut indices = HashMap::new();
+        for node in &self.nodes {
+            indices.insert(node.id, graph.add_node(node));
+        }
+        for edge in &self.edges {
+            if let (Some(from), Some(to)) = (indices.get(&edge.from), indices.get(&edge.to)) {
+                graph.add_edge(*from, *to, edge.label.as_str());
+            }
+        }
+        (graph, indices)
+    }
+
+    pub fn select(&self, selector: &str) -> Vec<usize> {
+        #[cfg(feature = "serde")]
+        if selector.starts_with('.') {
+            let (path, expected) = match selector.split_once("==") {
+                Some((path, expected)) => {
+                    let expected = expected.trim();
+                    let expected = serde_json::from_str(expected)
+                        .unwrap_or_else(|_| serde_json::Value::from(expected));
+                    (path.trim(), Some(expected))
+                },
+                None => (selector.trim(), None),
+            };
+            return self.nodes.iter()
+                .filter(|node| node.data.as_ref()
+                    .and_then(|data| lookup(data, path))
+                    .is_some_and(|found| expected.as_ref().map_or(true, |expected| found == expected)))
+                .map(|node| node.id)
+                .collect();
+        }
+        if let Ok(id) = selector.parse::<usize>() {
+            return self.nodes.iter().filter(|node| node.id == id).map(|node| node.id).collect();
+        }
+        let (function, var) = match selector.rsplit_once("::") {
+            Some((function, var)) => (Some(function), var),
+            None => (None, selector),
+        };
+        self.nodes.iter()
+            .filter(|node| node.var.as_deref() == Some(var))
+            .filter(|node| function.is_none() || node.function.as_deref() == function)
+            .map(|node| node.id)
+            .collect()
+    }
+
+    /// Returns the sub-trace reachable from `roots` in the given direction,
+    /// including the roots themselves.
+    ///
+    /// Slices are not executions, so they carry no events.
+    pub fn slice(&self, roots: &[usize], direction: Direction) -> Trace {
+        let (graph, indices) = self.graph();
+        let mut keep = HashSet::new();
+        for root in roots {
+            let Some(start) = indices.get(root).copied() else { continue };
+            let reached: Vec<NodeIndex> = match direction {
+                Direction::Why => Dfs::new(Reversed(&graph), start).iter(Reversed(&graph)).collect(),
+                Direction::Impact => Dfs::new(&graph, start).iter(&graph).collect(),
+            };
+            keep.extend(reached.into_iter().map(|ix| graph[ix].id));
+        }
+        self.subset(&keep)
+    }
+
+    /// Returns the sub-trace made of the nodes in `keep` and the edges
+    /// between them, with no events.
+    pub fn subset(&self, keep: &HashSet<usize>) -> Trace {
+        Trace {
+            nodes: self.nodes.iter().filter(|node| keep.contains(&node.id)).cloned().collect(),
+            edges: self.edges.iter().filter(|edge| keep.contains(&edge.from) && keep.contains(&edge.to)).cloned().collect(),
+            events: vec![],
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats{
+            nodes: self.nodes.len(),
+            edges: self.edges.len(),
+            ..Default::default()
+        };
+        for node in &self.nodes {
+            *stats.frames.entry(node.frame).or_default() += 1;
+            *stats.functions.entry(node.function.clone()).or_default() += 1;
+        }
+        stats
+    }
+
+    /// Compares two traces node by node.
+    ///
+    /// Frames are numbered differently from run to run, so nodes are
+    /// matched by their function, slot, and the order in which they were
+    /// recorded at that slot.
+    pub fn diff(&self, other: &Trace) -> Vec<Change> {
+        type Key<'a> = (Option<&'a str>, u64, usize);
+        fn keyed(trace: &Trace) -> Vec<(Key<'_>, &Node)> {
+            let mut seen: HashMap<(Option<&str>, u64), usize> = HashMap::new();
+            trace.nodes.iter().map(|node| {
+                let count = seen.entry((node.function.as_deref(), node.slot)).or_default();
+                *count += 1;
+                ((node.function.as_deref(), node.slot, *count), node)
+            }).collect()
+        }
+        let old = keyed(self);
+        let new = keyed(other);
+        let new_map: HashMap<_, _> = new.iter().cloned().collect();
+        let old_map: HashMap<_, _> = old.iter().cloned().collect();
+
+        let mut changes = vec![];
+        for (key, node) in &old {
+            match new_map.get(key) {
+                None => changes.push(Change::Removed((*node).clone())),
+                Some(other) if other.var != node.var || other.val != node.val => {
+                    changes.push(Change::Changed((*node).clone(), (*other).clone()));
+                },
+                Some(_) => {},
+            }
+        }
+        for (key, node) in &new {
+            if !old_map.contains_key(key) {
+                changes.push(Change::Added((*node).clone()));
+            }
+        }
+        changes
+    }
+}
+
+/// Returns the part of `data` at `path`, like `.status` or `.items.0`.
+#[cfg(feature = "serde")]
+fn lookup<'a>(data: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.').filter(|key| !key.is_empty()).try_fold(data, |data, key| match data {
+        serde_json::Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => data.get(key),
+    })
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "nodes: {}", self.nodes)?;
+        writeln!(f, "edges: {}", self.edges)?;
+        writeln!(f, "frames:")?;
+        for (frame, count) in &self.frames {
+            writeln!(f, "    {frame}: {count}")?;
+        }
+        writeln!(f, "functions:")?;
+        for (function, count) in &self.functions {
+            writeln!(f, "    {}: {count}", function.as_deref().unwrap_or("<toplevel>"))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added(node) => write!(f, "+ {} {node}", node.id),
+            Change::Removed(node) => write!(f, "- {} {node}", node.id),
+            Change::Changed(old, new) => write!(f, "~ {} {old} -> {} {new}", old.id, new.id),
+        }
+    }
+}
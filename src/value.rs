@@ -0,0 +1,96 @@
+//! # Overview
+//!
+//! [WyeValue] lets types decide how they appear in traces: as a short
+//! label, an optional long form, and named child components, like fields or
+//! elements, which the [Logger](crate::Logger) records as nodes of their
+//! own, linked to their parent by edges labeled with their names.
+//!
+//! # Example
+//!
+//! ```rust
+//! use wye::{Value, WyeValue};
+//!
+//! #[derive(WyeValue)]
+//! struct Login {
+//!     user: String,
+//!     #[wye(redact)]
+//!     password: String,
+//!     #[wye(skip)]
+//!     attempts: u64,
+//! }
+//!
+//! let login = Login{user: "ann".into(), password: "hunter2".into(), attempts: 3};
+//! let value = Value::new(&login);
+//! let children = value.children.iter()
+//!     .map(|(name, child)| (name.as_str(), child.label.as_str()))
+//!     .collect::<Vec<_>>();
+//! assert_eq!(value.label, "Login");
+//! assert_eq!(children, [("user", "\"ann\""), ("password", "<redacted>")]);
+//! ```
+//!
+//! Deriving [WyeValue] labels structs with their name and enums with their
+//! variant's path, and records every field as a child, formatted as
+//! described in [capture](crate::capture). Fields marked `#[wye(skip)]`
+//! are left out and fields marked `#[wye(redact)]` are recorded as
+//! `<redacted>`.
+
+/// How a type appears in traces; see above.
+pub trait WyeValue {
+    /// A short, one-line description of the value, used as its node's label.
+    fn label(&self) -> String;
+
+    /// A longer description of the value, if any.
+    fn long(&self) -> Option<String> {
+        None
+    }
+
+    /// The value's named components, each of which is recorded as a node.
+    fn children(&self) -> Vec<(String, Value)> {
+        vec![]
+    }
+}
+
+/// A value in structured form; see [Value::data].
+#[cfg(feature = "serde")]
+pub type Data = serde_json::Value;
+
+/// A value in structured form, which, without the `serde` feature, no value
+/// has.
+#[cfg(not(feature = "serde"))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Data {}
+
+/// A recorded value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Value {
+    pub label: String,
+    pub long: Option<String>,
+    /// The value in structured form, if the `serde` feature is enabled and
+    /// its type implements `serde::Serialize`; see
+    /// [capture](crate::capture#structured-values).
+    pub data: Option<Data>,
+    pub children: Vec<(String, Value)>,
+}
+
+impl Value {
+    pub fn new(value: &(impl WyeValue + ?Sized)) -> Self {
+        Self{
+            label: value.label(),
+            long: value.long(),
+            data: None,
+            children: value.children(),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(label: String) -> Self {
+        Self{label, ..Default::default()}
+    }
+}
+
+impl From<&str> for Value {
+    fn from(label: &str) -> Self {
+        label.to_string().into()
+    }
+}
@@ -0,0 +1,30 @@
+// Check that build scripts can inline module trees for instrumentation.
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn inline() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/build/app.rs");
+    assert_eq!(wye::build::inline(&root).unwrap(), r#"//! The application.
+
+pub mod net {
+pub(crate) mod tcp {
+pub const PORT: u64 = 80;
+}
+
+pub fn port() -> u64 {
+    tcp::PORT
+}
+}
+mod util {
+pub fn double(x: u64) -> u64 {
+    x * 2
+}
+}
+
+pub fn run() -> u64 {
+    util::double(net::port())
+}
+"#);
+}
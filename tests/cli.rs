@@ -0,0 +1,188 @@
+// Check the `wye` command-line tool against saved fixture traces.
+use std::{io::Write, process::{Command, Output, Stdio}};
+
+use pretty_assertions::assert_eq;
+
+const LET: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/let.json");
+const LET_CHANGED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/let-changed.json");
+const STRUCTURED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/structured.json");
+const SITES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/sites.jsonl");
+
+fn wye(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_wye")).args(args).output().unwrap()
+}
+
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn render_text() {
+    assert_eq!(stdout(wye(&["render", LET])), r#"0 [<toplevel>] five = 5 <- 5
+1 [<toplevel>] 2
+2 [<toplevel>] 3
+3 [add] a = 2 <- 1
+4 [add] b = 3 <- 2
+5 [add] + = 5 <- 3, 4
+6 [<toplevel>] add(1, five) = 6 <- 10, 0
+7 [<toplevel>] 1
+8 [add] a = 1 <- 7
+9 [add] b = 5 <- 0
+10 [add] + = 6 <- 8, 9
+"#);
+}
+
+#[test]
+fn render_dot() {
+    assert_eq!(stdout(wye(&["render", "--format", "dot", LET])), r#"digraph {
+    0 [ label = "five = 5" ]
+    1 [ label = "2" ]
+    2 [ label = "3" ]
+    3 [ label = "a = 2" ]
+    4 [ label = "b = 3" ]
+    5 [ label = "+ = 5" ]
+    6 [ label = "add(1, five) = 6" ]
+    7 [ label = "1" ]
+    8 [ label = "a = 1" ]
+    9 [ label = "b = 5" ]
+    10 [ label = "+ = 6" ]
+    1 -> 3 [ label = "" ]
+    2 -> 4 [ label = "" ]
+    3 -> 5 [ label = "" ]
+    4 -> 5 [ label = "" ]
+    5 -> 0 [ label = "" ]
+    7 -> 8 [ label = "" ]
+    0 -> 9 [ label = "" ]
+    8 -> 10 [ label = "" ]
+    9 -> 10 [ label = "" ]
+    10 -> 6 [ label = "" ]
+    0 -> 6 [ label = "" ]
+}
+"#);
+}
+
+#[test]
+fn render_mermaid_and_html() {
+    let mermaid = stdout(wye(&["render", "--format=mermaid", LET]));
+    assert!(mermaid.starts_with("flowchart TD\n    n0[\"five = 5\"]\n"));
+    assert!(mermaid.contains("    n5 --> n0\n"));
+
+    let html = stdout(wye(&["render", "-f", "html", LET]));
+    assert!(html.contains("<pre class=\"mermaid\">\nflowchart TD\n"));
+    assert!(html.contains("n5 --&gt; n0"));
+}
+
+#[test]
+fn render_stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wye"))
+        .args(["stats", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(&std::fs::read(LET).unwrap()).unwrap();
+    assert_eq!(stdout(child.wait_with_output().unwrap()), r#"nodes: 11
+edges: 11
+frames:
+    0: 6
+    1: 5
+functions:
+    <toplevel>: 5
+    add: 6
+"#);
+}
+
+#[test]
+fn why() {
+    assert_eq!(stdout(wye(&["why", "five", LET])), r#"0 [<toplevel>] five = 5 <- 5
+1 [<toplevel>] 2
+2 [<toplevel>] 3
+3 [add] a = 2 <- 1
+4 [add] b = 3 <- 2
+5 [add] + = 5 <- 3, 4
+"#);
+}
+
+#[test]
+fn impact() {
+    assert_eq!(stdout(wye(&["impact", "2", LET])), r#"0 [<toplevel>] five = 5 <- 5
+2 [<toplevel>] 3
+4 [add] b = 3 <- 2
+5 [add] + = 5 <- 4
+6 [<toplevel>] add(1, five) = 6 <- 10, 0
+9 [add] b = 5 <- 0
+10 [add] + = 6 <- 9
+"#);
+    assert_eq!(stdout(wye(&["impact", "--format", "dot", "add::b", LET])), r#"digraph {
+    0 [ label = "five = 5" ]
+    4 [ label = "b = 3" ]
+    5 [ label = "+ = 5" ]
+    6 [ label = "add(1, five) = 6" ]
+    9 [ label = "b = 5" ]
+    10 [ label = "+ = 6" ]
+    4 -> 5 [ label = "" ]
+    5 -> 0 [ label = "" ]
+    0 -> 9 [ label = "" ]
+    9 -> 10 [ label = "" ]
+    10 -> 6 [ label = "" ]
+    0 -> 6 [ label = "" ]
+}
+"#);
+}
+
+#[test]
+fn diff() {
+    let output = wye(&["diff", LET, LET_CHANGED]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), r#"~ 0 five = 5 -> 0 five = 6
+~ 2 3 -> 2 4
+~ 4 b = 3 -> 4 b = 4
+~ 5 + = 5 -> 5 + = 6
+~ 6 add(1, five) = 6 -> 6 add(1, five) = 7
+~ 9 b = 5 -> 9 b = 6
+~ 10 + = 6 -> 10 + = 7
+"#);
+
+    let output = wye(&["diff", LET, LET]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn select() {
+    assert_eq!(stdout(wye(&["select", ".status == 500", STRUCTURED])), r#"3 [respond] Response { status: 500, user: "bob" }
+"#);
+    assert_eq!(stdout(wye(&["select", ". == 500", STRUCTURED])), "2 [respond] status = 500\n");
+    assert_eq!(stdout(wye(&["select", ".user == ann", STRUCTURED])), stdout(wye(&["select", ".user == \"ann\"", STRUCTURED])));
+    assert_eq!(stdout(wye(&["select", "status", STRUCTURED])), "0 [respond] status = 200\n2 [respond] status = 500\n");
+
+    let output = wye(&["select", ".status == 404", STRUCTURED]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn coverage() {
+    assert_eq!(stdout(wye(&["coverage", SITES, LET])), r#"sites: 3/5 executed
+functions:
+    demo::add: 3/3
+    demo::double: 0/2
+never executed:
+    src/main.rs:7:8 Param a
+    src/main.rs:7:28 Expr::Binary(*) a * 2
+"#);
+}
+
+#[test]
+fn errors() {
+    let output = wye(&["why", "nope", LET]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "wye: no nodes match selector: nope\n");
+
+    let output = wye(&["render", "--format", "svg", LET]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = wye(&["frobnicate"]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,77 @@
+// Check that a Cursor can step through a recorded run in both directions.
+use wye::*;
+use wye::{cursor::Cursor, trace::{EventKind, Trace}};
+use pretty_assertions::assert_eq;
+
+#[wye]
+fn add(a: u64, b: u64) -> u64 { a + b }
+
+/// Returns the seq of the event defining the `nth` node labeled `label`.
+fn defined(trace: &Trace, label: &str, nth: usize) -> u64 {
+    trace.events.iter()
+        .filter(|event| matches!(event.kind, EventKind::Define{node} if trace.nodes[node].to_string() == label))
+        .nth(nth)
+        .unwrap()
+        .seq
+}
+
+fn variables(cursor: &Cursor) -> Vec<String> {
+    cursor.variables().iter().map(|(_, node)| node.to_string()).collect()
+}
+
+#[test]
+fn cursor() {
+    assert_eq!(wyre!{
+        let five = add(2, 3);
+        add(1, five)
+    }, 6);
+    let trace = get_wye().trace();
+
+    for (ix, event) in trace.events.iter().enumerate() {
+        assert_eq!(event.seq, ix as u64);
+    }
+
+    let mut cursor = Cursor::new(&trace);
+    assert_eq!(cursor.position(), 0);
+    assert_eq!(cursor.event(), None);
+    assert_eq!(cursor.step_back(), None);
+    assert!(cursor.variables().is_empty());
+
+    // inside the second call to add:
+    let b = defined(&trace, "b = 5", 0);
+    cursor.seek(b).unwrap();
+    assert_eq!(cursor.function(), Some("add"));
+    assert_eq!(variables(&cursor), vec!["a = 1", "b = 5"]);
+    let depth = cursor.depth();
+
+    // ...which returns to the wyre! block:
+    let pop = cursor.step_out().unwrap();
+    assert_eq!(pop.kind, EventKind::PopFrame);
+    assert_eq!(cursor.depth(), depth - 1);
+    assert_eq!(cursor.function(), None);
+    assert_eq!(variables(&cursor), vec!["five = 5"]);
+
+    // ...and was entered by a push that step_over_back skips back across:
+    let push = cursor.step_over_back().unwrap();
+    assert_eq!(push.kind, EventKind::PushFrame);
+    assert_eq!(cursor.depth(), depth - 1);
+    let before_push = cursor.position();
+    assert_eq!(cursor.step_over().unwrap(), pop);
+
+    cursor.seek(b).unwrap();
+    assert_eq!(cursor.step_out_back().unwrap(), push);
+    assert_eq!(cursor.position(), before_push);
+
+    // stepping one event at a time is reversible:
+    let five = defined(&trace, "five = 5", 0);
+    cursor.seek(five).unwrap();
+    assert_eq!(variables(&cursor), vec!["five = 5"]);
+    let event = cursor.step_back().unwrap();
+    assert_eq!(event.seq, five);
+    assert!(cursor.variables().is_empty());
+    assert_eq!(cursor.step_forward().unwrap(), event);
+
+    while cursor.step_forward().is_some() {}
+    assert_eq!(cursor.position(), trace.events.len());
+    assert_eq!(cursor.event(), trace.events.last());
+}
@@ -0,0 +1,8 @@
+//! The application.
+
+pub mod net;
+mod util;
+
+pub fn run() -> u64 {
+    util::double(net::port())
+}
@@ -0,0 +1,5 @@
+pub(crate) mod tcp;
+
+pub fn port() -> u64 {
+    tcp::PORT
+}
@@ -0,0 +1 @@
+pub const PORT: u64 = 80;
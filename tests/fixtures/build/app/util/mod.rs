@@ -0,0 +1,3 @@
+pub fn double(x: u64) -> u64 {
+    x * 2
+}
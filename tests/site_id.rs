@@ -0,0 +1,35 @@
+// Check that site IDs match the documented FNV-1a construction.
+use wye::*;
+use wye::trace::{site_id, Location};
+
+#[wye]
+fn add(a: u64, b: u64) -> u64 { a + b }
+
+#[test]
+fn known_answers() {
+    assert_eq!(site_id("demo", "src/lib.rs", (3, 5), (3, 14)), 0x5628_8a65_ef44_72a4);
+    assert_eq!(site_id("demo", "src/main.rs", (3, 5), (3, 14)), 0x1b46_2204_6bca_ddb8);
+}
+
+#[test]
+fn location() {
+    let location = Location{
+        file: "src/lib.rs".into(),
+        start: (3, 5),
+        end: (3, 14),
+        text: "add(1, 2)".into(),
+    };
+    assert_eq!(location.site_id("demo"), site_id("demo", "src/lib.rs", (3, 5), (3, 14)));
+}
+
+#[test]
+fn relative_file() {
+    assert_eq!(wyre!{add(1, 2)}, 3);
+    let trace = get_wye().trace();
+    assert!(!trace.nodes.is_empty());
+    for node in &trace.nodes {
+        let location = node.location.as_ref().unwrap();
+        assert_eq!(location.file, "tests/site_id.rs", "{node}");
+        assert_eq!(node.slot, location.site_id(env!("CARGO_CRATE_NAME")), "{node}");
+    }
+}